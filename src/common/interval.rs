@@ -0,0 +1,128 @@
+use std::fmt;
+
+// closed interval of integers, start is never greater than end
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Interval {
+    pub start: i64,
+    pub end: i64,
+}
+
+impl Interval {
+    pub fn new(start: i64, end: i64) -> Interval {
+        assert!(start <= end, "Interval {}-{} is reversed", start, end);
+
+        return Interval { start, end };
+    }
+
+    pub fn len(&self) -> i64 {
+        return self.end - self.start + 1;
+    }
+
+    pub fn contains_point(&self, point: i64) -> bool {
+        return self.start <= point && point <= self.end;
+    }
+
+    pub fn contains(&self, other: &Interval) -> bool {
+        return self.start <= other.start && other.end <= self.end;
+    }
+
+    pub fn overlaps(&self, other: &Interval) -> bool {
+        return self.start <= other.end && other.start <= self.end;
+    }
+
+    // true also for intervals which only touch, e.g. 1-2 and 3-4
+    pub fn is_adjacent_or_overlapping(&self, other: &Interval) -> bool {
+        return self.start <= other.end.saturating_add(1)
+            && other.start <= self.end.saturating_add(1);
+    }
+
+    pub fn intersection(&self, other: &Interval) -> Option<Interval> {
+        if !self.overlaps(other) {
+            return None;
+        }
+
+        return Some(Interval::new(
+            self.start.max(other.start),
+            self.end.min(other.end),
+        ));
+    }
+
+    // None when a gap between the intervals would have to be included
+    pub fn union(&self, other: &Interval) -> Option<Interval> {
+        if !self.is_adjacent_or_overlapping(other) {
+            return None;
+        }
+
+        return Some(Interval::new(
+            self.start.min(other.start),
+            self.end.max(other.end),
+        ));
+    }
+
+    // parts of this interval not covered by the other one, at most two of them
+    pub fn difference(&self, other: &Interval) -> Vec<Interval> {
+        if !self.overlaps(other) {
+            return vec![*self];
+        }
+
+        let mut parts = vec![];
+
+        if self.start < other.start {
+            parts.push(Interval::new(self.start, other.start - 1));
+        }
+        if other.end < self.end {
+            parts.push(Interval::new(other.end + 1, self.end));
+        }
+
+        return parts;
+    }
+}
+
+impl fmt::Display for Interval {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}-{}", self.start, self.end)
+    }
+}
+
+// sorted, non overlapping intervals covering exactly the same points
+pub fn merge_intervals(intervals: &[Interval]) -> Vec<Interval> {
+    let mut sorted_intervals = intervals.to_vec();
+    sorted_intervals.sort();
+
+    let mut merged: Vec<Interval> = vec![];
+
+    for interval in sorted_intervals {
+        match merged.last_mut() {
+            Some(last) if last.is_adjacent_or_overlapping(&interval) => {
+                *last = last.union(&interval).unwrap();
+            }
+            _ => merged.push(interval),
+        }
+    }
+
+    return merged;
+}
+
+// splits the covered points into intervals of constant depth, i.e. amount of covering intervals
+pub fn get_coverage_depths(intervals: &[Interval]) -> Vec<(Interval, usize)> {
+    let mut events: Vec<(i64, i64)> = intervals
+        .iter()
+        .flat_map(|interval| [(interval.start, 1), (interval.end + 1, -1)])
+        .collect();
+    events.sort();
+
+    let mut depths = vec![];
+    let mut depth = 0;
+
+    for (index, (point, change)) in events.iter().enumerate() {
+        depth += change;
+
+        if let Some((next_point, _)) = events.get(index + 1) {
+            if depth > 0 && next_point > point {
+                depths.push((Interval::new(*point, next_point - 1), depth as usize));
+            }
+        }
+    }
+
+    return depths;
+}
@@ -0,0 +1,235 @@
+use crate::common::interval::Interval;
+
+// static interval tree, entries sorted by start form an implicit balanced tree where the middle
+// of every slice is its root, each root keeps the max and min end of its subtree for pruning
+pub struct IntervalIndex<T> {
+    entries: Vec<(Interval, T)>,
+    max_ends: Vec<i64>,
+    min_ends: Vec<i64>,
+}
+
+impl<T> IntervalIndex<T> {
+    pub fn new(mut entries: Vec<(Interval, T)>) -> IntervalIndex<T> {
+        entries.sort_by_key(|(interval, _)| *interval);
+
+        let mut index = IntervalIndex {
+            max_ends: vec![0; entries.len()],
+            min_ends: vec![0; entries.len()],
+            entries,
+        };
+        index.build(0, index.entries.len());
+
+        return index;
+    }
+
+    fn build(&mut self, low: usize, high: usize) -> Option<(i64, i64)> {
+        if low >= high {
+            return None;
+        }
+
+        let middle = (low + high) / 2;
+        let end = self.entries[middle].0.end;
+        let (mut max_end, mut min_end) = (end, end);
+
+        let subtrees = [self.build(low, middle), self.build(middle + 1, high)];
+
+        for (subtree_max_end, subtree_min_end) in subtrees.into_iter().flatten() {
+            max_end = max_end.max(subtree_max_end);
+            min_end = min_end.min(subtree_min_end);
+        }
+
+        self.max_ends[middle] = max_end;
+        self.min_ends[middle] = min_end;
+
+        return Some((max_end, min_end));
+    }
+
+    pub fn len(&self) -> usize {
+        return self.entries.len();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.entries.is_empty();
+    }
+
+    pub fn find_containing_point(&self, point: i64) -> Vec<&T> {
+        return self.find_overlapping(&Interval::new(point, point));
+    }
+
+    pub fn find_overlapping(&self, query: &Interval) -> Vec<&T> {
+        let mut found = vec![];
+        self.collect_overlapping(0, self.entries.len(), query, &mut found);
+
+        return found;
+    }
+
+    // entries whose interval contains the whole query
+    pub fn find_containing(&self, query: &Interval) -> Vec<&T> {
+        let mut found = vec![];
+        self.collect_containing(0, self.entries.len(), query, &mut found);
+
+        return found;
+    }
+
+    // entries whose interval lies within the query
+    pub fn find_contained(&self, query: &Interval) -> Vec<&T> {
+        let mut found = vec![];
+        self.collect_contained(0, self.entries.len(), query, &mut found);
+
+        return found;
+    }
+
+    fn collect_overlapping<'a>(
+        &'a self,
+        low: usize,
+        high: usize,
+        query: &Interval,
+        found: &mut Vec<&'a T>,
+    ) {
+        if low >= high {
+            return;
+        }
+
+        let middle = (low + high) / 2;
+
+        if self.max_ends[middle] < query.start {
+            return;
+        }
+
+        self.collect_overlapping(low, middle, query, found);
+
+        let (interval, value) = &self.entries[middle];
+
+        if interval.start > query.end {
+            return;
+        }
+        if interval.overlaps(query) {
+            found.push(value);
+        }
+
+        self.collect_overlapping(middle + 1, high, query, found);
+    }
+
+    fn collect_containing<'a>(
+        &'a self,
+        low: usize,
+        high: usize,
+        query: &Interval,
+        found: &mut Vec<&'a T>,
+    ) {
+        if low >= high {
+            return;
+        }
+
+        let middle = (low + high) / 2;
+
+        if self.max_ends[middle] < query.end {
+            return;
+        }
+
+        self.collect_containing(low, middle, query, found);
+
+        let (interval, value) = &self.entries[middle];
+
+        if interval.start > query.start {
+            return;
+        }
+        if interval.contains(query) {
+            found.push(value);
+        }
+
+        self.collect_containing(middle + 1, high, query, found);
+    }
+
+    fn collect_contained<'a>(
+        &'a self,
+        low: usize,
+        high: usize,
+        query: &Interval,
+        found: &mut Vec<&'a T>,
+    ) {
+        if low >= high {
+            return;
+        }
+
+        let middle = (low + high) / 2;
+
+        if self.min_ends[middle] > query.end {
+            return;
+        }
+
+        let (interval, value) = &self.entries[middle];
+
+        if interval.start >= query.start {
+            self.collect_contained(low, middle, query, found);
+        }
+        if query.contains(interval) {
+            found.push(value);
+        }
+        if interval.start <= query.end {
+            self.collect_contained(middle + 1, high, query, found);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::random::Random;
+
+    fn get_random_interval(random: &mut Random, bound: usize) -> Interval {
+        let start = random.next_below(bound);
+        let end = random.next_in_range(start, bound);
+
+        return Interval::new(start as i64 - 5, end as i64 - 5);
+    }
+
+    fn sorted(mut values: Vec<&usize>) -> Vec<usize> {
+        values.sort();
+
+        return values.into_iter().copied().collect();
+    }
+
+    fn scan(intervals: &Vec<Interval>, matches: impl Fn(&Interval) -> bool) -> Vec<usize> {
+        return (0..intervals.len())
+            .filter(|index| matches(&intervals[*index]))
+            .collect();
+    }
+
+    #[test]
+    fn matches_linear_scan() {
+        let mut random = Random::new(36);
+
+        for _ in 0..500 {
+            let bound = random.next_in_range(1, 40);
+            let intervals: Vec<Interval> = (0..random.next_below(30))
+                .map(|_| get_random_interval(&mut random, bound))
+                .collect();
+            let index = IntervalIndex::new(intervals.iter().copied().zip(0..).collect());
+
+            assert_eq!(index.len(), intervals.len());
+
+            for _ in 0..20 {
+                let query = get_random_interval(&mut random, bound + 10);
+                let point = random.next_below(bound + 10) as i64 - 5;
+
+                assert_eq!(
+                    sorted(index.find_overlapping(&query)),
+                    scan(&intervals, |interval| interval.overlaps(&query))
+                );
+                assert_eq!(
+                    sorted(index.find_containing(&query)),
+                    scan(&intervals, |interval| interval.contains(&query))
+                );
+                assert_eq!(
+                    sorted(index.find_contained(&query)),
+                    scan(&intervals, |interval| query.contains(interval))
+                );
+                assert_eq!(
+                    sorted(index.find_containing_point(point)),
+                    scan(&intervals, |interval| interval.contains_point(point))
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,115 @@
+use std::ops::{BitAnd, BitOr};
+
+const WORD_BITS: usize = u64::BITS as usize;
+const WORDS_COUNT: usize = 4;
+
+pub const MAX_ITEMS: usize = WORD_BITS * WORDS_COUNT;
+
+// set of item indices in range 0..MAX_ITEMS, each index is one bit of a fixed size mask, so sets
+// are copied and intersected without allocating
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ItemSet([u64; WORDS_COUNT]);
+
+impl ItemSet {
+    pub fn new() -> ItemSet {
+        return ItemSet([0; WORDS_COUNT]);
+    }
+
+    // every index in range 0..items_count
+    pub fn full(items_count: usize) -> ItemSet {
+        assert!(
+            items_count <= MAX_ITEMS,
+            "{} items do not fit into item set",
+            items_count
+        );
+
+        let mut item_set = ItemSet::new();
+
+        for (word_index, word) in item_set.0.iter_mut().enumerate() {
+            let bits = items_count.saturating_sub(word_index * WORD_BITS);
+
+            *word = match bits {
+                0 => 0,
+                bits if bits >= WORD_BITS => u64::MAX,
+                bits => (1 << bits) - 1,
+            };
+        }
+
+        return item_set;
+    }
+
+    pub fn from_indices(indices: impl IntoIterator<Item = usize>) -> ItemSet {
+        let mut item_set = ItemSet::new();
+
+        for index in indices {
+            item_set.insert(index);
+        }
+
+        return item_set;
+    }
+
+    pub fn insert(&mut self, index: usize) {
+        assert!(
+            index < MAX_ITEMS,
+            "Index {} does not fit into item set",
+            index
+        );
+
+        self.0[index / WORD_BITS] |= 1 << (index % WORD_BITS);
+    }
+
+    pub fn contains(&self, index: usize) -> bool {
+        return index < MAX_ITEMS && self.0[index / WORD_BITS] & (1 << (index % WORD_BITS)) != 0;
+    }
+
+    pub fn intersection(&self, other: &ItemSet) -> ItemSet {
+        let mut words = self.0;
+
+        for (word, other_word) in words.iter_mut().zip(other.0) {
+            *word &= other_word;
+        }
+
+        return ItemSet(words);
+    }
+
+    pub fn union(&self, other: &ItemSet) -> ItemSet {
+        let mut words = self.0;
+
+        for (word, other_word) in words.iter_mut().zip(other.0) {
+            *word |= other_word;
+        }
+
+        return ItemSet(words);
+    }
+
+    pub fn len(&self) -> u32 {
+        return self.0.iter().map(|word| word.count_ones()).sum();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.0.iter().all(|word| *word == 0);
+    }
+
+    pub fn indices(&self) -> impl Iterator<Item = usize> {
+        let words = self.0;
+
+        return (0..MAX_ITEMS)
+            .filter(move |index| words[index / WORD_BITS] & (1 << (index % WORD_BITS)) != 0);
+    }
+}
+
+impl BitAnd for ItemSet {
+    type Output = ItemSet;
+
+    fn bitand(self, other: ItemSet) -> ItemSet {
+        return self.intersection(&other);
+    }
+}
+
+impl BitOr for ItemSet {
+    type Output = ItemSet;
+
+    fn bitor(self, other: ItemSet) -> ItemSet {
+        return self.union(&other);
+    }
+}
@@ -0,0 +1,5 @@
+#![allow(dead_code)]
+pub mod interval;
+pub mod interval_index;
+pub mod item_set;
+pub mod random;
@@ -0,0 +1,30 @@
+// splitmix64, small deterministic generator so that generated inputs are reproducible by seed
+pub struct Random {
+    state: u64,
+}
+
+impl Random {
+    pub fn new(seed: u64) -> Random {
+        return Random { state: seed };
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E3779B97F4A7C15);
+
+        let mut value = self.state;
+        value = (value ^ (value >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        value = (value ^ (value >> 27)).wrapping_mul(0x94D049BB133111EB);
+
+        return value ^ (value >> 31);
+    }
+
+    // uniform value in range 0..bound, bound has to be positive
+    pub fn next_below(&mut self, bound: usize) -> usize {
+        return (self.next_u64() % bound as u64) as usize;
+    }
+
+    // uniform value in range low..=high
+    pub fn next_in_range(&mut self, low: usize, high: usize) -> usize {
+        return low + self.next_below(high - low + 1);
+    }
+}
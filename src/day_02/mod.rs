@@ -1,15 +1,17 @@
 #![allow(dead_code)]
 #![allow(unused_variables)]
-use std::fs;
+use std::{cmp::Reverse, fs};
 
 const FILE_PATH: &str = "src/day_02/resources/input.txt";
 
+#[derive(Clone, Copy, Debug)]
 enum Item {
     Rock,
     Paper,
     Scissors,
 }
 
+#[derive(Clone, Copy, Debug)]
 enum Result {
     Win,
     Lose,
@@ -90,15 +92,22 @@ fn get_your_item_by_result_and_oponnent(opponent: &Item, wanted_result: &Result)
     }
 }
 
-fn get_round_score_1(round: &Vec<&str>) -> i32 {
-    if let [oponnent, you] = &round[..] {
-        let your_item = get_item(you);
-        let opponent_item = get_item(oponnent);
+fn get_round_score_by_items(opponent_item: &Item, your_item: &Item) -> i32 {
+    let your_item_value = get_item_value(your_item);
+    let result_value = get_result_value(&check_result(your_item, opponent_item));
+
+    return your_item_value + result_value;
+}
 
-        let your_item_value = get_item_value(&your_item);
-        let result_value = get_result_value(&check_result(&your_item, &opponent_item));
+fn get_round_score_by_result(opponent_item: &Item, wanted_result: &Result) -> i32 {
+    let your_item = get_your_item_by_result_and_oponnent(opponent_item, wanted_result);
 
-        return your_item_value + result_value;
+    return get_result_value(wanted_result) + get_item_value(&your_item);
+}
+
+fn get_round_score_1(round: &Vec<&str>) -> i32 {
+    if let [oponnent, you] = &round[..] {
+        return get_round_score_by_items(&get_item(oponnent), &get_item(you));
     }
 
     return 0;
@@ -106,22 +115,574 @@ fn get_round_score_1(round: &Vec<&str>) -> i32 {
 
 fn get_round_score_2(round: &Vec<&str>) -> i32 {
     if let [oponnent, you] = &round[..] {
-        let oponent_item = get_item(oponnent);
-        let wanted_result = get_wanted_result(you);
-        let your_item = get_your_item_by_result_and_oponnent(&oponent_item, &wanted_result);
-
-        return get_result_value(&wanted_result) + get_item_value(&your_item);
+        return get_round_score_by_result(&get_item(oponnent), &get_wanted_result(you));
     }
     return 0;
 }
 
-pub fn execute() {
-    let content = fs::read_to_string(FILE_PATH).expect("Should have been able to read the file");
+// possible meanings of the second guide column, values are ordered as X, Y, Z
+enum ColumnMapping {
+    Items([Item; 3]),
+    Results([Result; 3]),
+}
+
+struct ColumnMappingScore {
+    mapping: ColumnMapping,
+    score: i32,
+}
+
+impl ColumnMapping {
+    // malformed rounds score nothing
+    fn get_round_score(&self, round: &Vec<&str>) -> i32 {
+        if let Some((opponent_item, column_index)) = get_guide_round(round) {
+            return match self {
+                ColumnMapping::Items(items) => {
+                    get_round_score_by_items(&opponent_item, &items[column_index])
+                }
+                ColumnMapping::Results(results) => {
+                    get_round_score_by_result(&opponent_item, &results[column_index])
+                }
+            };
+        }
+
+        return 0;
+    }
+
+    fn describe(&self) -> String {
+        let values: Vec<String> = match self {
+            ColumnMapping::Items(items) => items.iter().map(|item| format!("{:?}", item)).collect(),
+            ColumnMapping::Results(results) => results
+                .iter()
+                .map(|result| format!("{:?}", result))
+                .collect(),
+        };
+
+        return format!("X={} Y={} Z={}", values[0], values[1], values[2]);
+    }
+}
+
+fn get_guide_column_index(raw_item: &str) -> Option<usize> {
+    match raw_item {
+        "X" => Some(0),
+        "Y" => Some(1),
+        "Z" => Some(2),
+        &_ => None,
+    }
+}
+
+fn get_opponent_item(round: &Vec<&str>) -> Option<Item> {
+    if let [oponnent @ ("A" | "B" | "C"), _] = round[..] {
+        return Some(get_item(oponnent));
+    }
+
+    return None;
+}
+
+// opponent item and second column index of a well formed guide round
+fn get_guide_round(round: &Vec<&str>) -> Option<(Item, usize)> {
+    if let [oponnent @ ("A" | "B" | "C"), you] = round[..] {
+        return get_guide_column_index(you).map(|column_index| (get_item(oponnent), column_index));
+    }
+
+    return None;
+}
+
+fn get_permutations<T: Copy>(values: [T; 3]) -> Vec<[T; 3]> {
+    let [first, second, third] = values;
+
+    return vec![
+        [first, second, third],
+        [first, third, second],
+        [second, first, third],
+        [second, third, first],
+        [third, first, second],
+        [third, second, first],
+    ];
+}
+
+// scores the guide under every shape and outcome meaning of the second column, best first
+fn rank_column_mappings(rounds: &Vec<Vec<&str>>) -> Vec<ColumnMappingScore> {
+    let items_mappings = get_permutations([Item::Rock, Item::Paper, Item::Scissors])
+        .into_iter()
+        .map(ColumnMapping::Items);
+    let results_mappings = get_permutations([Result::Lose, Result::Draw, Result::Win])
+        .into_iter()
+        .map(ColumnMapping::Results);
+
+    let mut mappings_scores: Vec<ColumnMappingScore> = items_mappings
+        .chain(results_mappings)
+        .map(|mapping| {
+            let score = rounds
+                .iter()
+                .map(|round| mapping.get_round_score(round))
+                .sum();
+
+            ColumnMappingScore { mapping, score }
+        })
+        .collect();
+
+    mappings_scores.sort_by_key(|mapping_score| Reverse(mapping_score.score));
+
+    return mappings_scores;
+}
+
+struct RoundReport {
+    number: usize,
+    opponent_item: Item,
+    your_item: Item,
+    result: Result,
+    item_points: i32,
+    result_points: i32,
+    cumulative_score: i32,
+}
+
+struct TournamentReport {
+    rounds: Vec<RoundReport>,
+    malformed_rounds: Vec<(usize, String)>,
+    wins: u32,
+    draws: u32,
+    losses: u32,
+    total_score: i32,
+}
+
+impl TournamentReport {
+    fn to_table(&self) -> String {
+        let mut table = format!(
+            "{:>5} {:<9} {:<9} {:<6} {:>5} {:>7} {:>10}\n",
+            "round", "opponent", "you", "result", "shape", "outcome", "cumulative"
+        );
+
+        for round in &self.rounds {
+            table.push_str(&format!(
+                "{:>5} {:<9} {:<9} {:<6} {:>5} {:>7} {:>10}\n",
+                round.number,
+                format!("{:?}", round.opponent_item),
+                format!("{:?}", round.your_item),
+                format!("{:?}", round.result),
+                round.item_points,
+                round.result_points,
+                round.cumulative_score
+            ));
+        }
+
+        for (number, line) in &self.malformed_rounds {
+            table.push_str(&format!("{:>5} malformed round {}\n", number, line));
+        }
+
+        table.push_str(&format!(
+            "wins {} draws {} losses {} total {}",
+            self.wins, self.draws, self.losses, self.total_score
+        ));
+
+        return table;
+    }
+
+    fn to_json(&self) -> String {
+        let rounds: Vec<String> = self
+            .rounds
+            .iter()
+            .map(|round| {
+                format!(
+                    "{{\"round\":{},\"opponent\":\"{:?}\",\"you\":\"{:?}\",\"result\":\"{:?}\",\"shape_points\":{},\"outcome_points\":{},\"cumulative_score\":{}}}",
+                    round.number,
+                    round.opponent_item,
+                    round.your_item,
+                    round.result,
+                    round.item_points,
+                    round.result_points,
+                    round.cumulative_score
+                )
+            })
+            .collect();
+
+        let malformed_rounds: Vec<String> = self
+            .malformed_rounds
+            .iter()
+            .map(|(number, _)| number.to_string())
+            .collect();
+
+        return format!(
+            "{{\"rounds\":[{}],\"malformed_rounds\":[{}],\"wins\":{},\"draws\":{},\"losses\":{},\"total_score\":{}}}",
+            rounds.join(","),
+            malformed_rounds.join(","),
+            self.wins,
+            self.draws,
+            self.losses,
+            self.total_score
+        );
+    }
+}
+
+// resolves both items of a round, second column is read as in part 1 or part 2
+fn get_round_items(round: &Vec<&str>, wanted_result_column: bool) -> Option<(Item, Item)> {
+    let (opponent_item, column_index) = get_guide_round(round)?;
+
+    let your_item = if wanted_result_column {
+        get_your_item_by_result_and_oponnent(
+            &opponent_item,
+            &[Result::Lose, Result::Draw, Result::Win][column_index],
+        )
+    } else {
+        ITEMS[column_index]
+    };
+
+    return Some((opponent_item, your_item));
+}
+
+// empty lines, like a trailing newline, are not rounds at all
+fn is_blank_round(round: &Vec<&str>) -> bool {
+    return round.iter().all(|column| column.trim().is_empty());
+}
+
+fn build_tournament_report(
+    rounds: &Vec<Vec<&str>>,
+    wanted_result_column: bool,
+) -> TournamentReport {
+    let mut report = TournamentReport {
+        rounds: vec![],
+        malformed_rounds: vec![],
+        wins: 0,
+        draws: 0,
+        losses: 0,
+        total_score: 0,
+    };
+
+    for (index, round) in rounds.iter().enumerate() {
+        if is_blank_round(round) {
+            continue;
+        }
+
+        let (opponent_item, your_item) = match get_round_items(round, wanted_result_column) {
+            Some(items) => items,
+            None => {
+                report.malformed_rounds.push((index + 1, round.join(" ")));
+
+                continue;
+            }
+        };
+
+        let result = check_result(&your_item, &opponent_item);
+        let item_points = get_item_value(&your_item);
+        let result_points = get_result_value(&result);
+
+        match result {
+            Result::Win => report.wins += 1,
+            Result::Draw => report.draws += 1,
+            Result::Lose => report.losses += 1,
+        }
+
+        report.total_score += item_points + result_points;
+        report.rounds.push(RoundReport {
+            number: index + 1,
+            opponent_item,
+            your_item,
+            result,
+            item_points,
+            result_points,
+            cumulative_score: report.total_score,
+        });
+    }
+
+    return report;
+}
+
+const ITEMS: [Item; 3] = [Item::Rock, Item::Paper, Item::Scissors];
+
+fn get_item_index(item: &Item) -> usize {
+    match item {
+        Item::Rock => 0,
+        Item::Paper => 1,
+        Item::Scissors => 2,
+    }
+}
+
+struct StrategyConstraints {
+    max_item_uses: Option<usize>,
+    required_losses: Option<usize>,
+}
+
+struct OptimalStrategy {
+    score: i32,
+    your_items: Vec<Item>,
+}
+
+struct FlowEdge {
+    to: usize,
+    capacity: i64,
+    cost: i64,
+}
+
+// successive shortest paths, edges are stored in pairs so that edge ^ 1 is the reverse one
+fn run_min_cost_flow(
+    nodes_count: usize,
+    edges: &mut Vec<FlowEdge>,
+    source: usize,
+    sink: usize,
+) -> i64 {
+    let mut total_flow = 0;
+
+    loop {
+        let mut distances = vec![i64::MAX; nodes_count];
+        let mut incoming_edges: Vec<Option<usize>> = vec![None; nodes_count];
+        distances[source] = 0;
+
+        for _ in 0..nodes_count {
+            for (edge_index, edge) in edges.iter().enumerate() {
+                let from = edges[edge_index ^ 1].to;
+
+                if edge.capacity > 0
+                    && distances[from] != i64::MAX
+                    && distances[from] + edge.cost < distances[edge.to]
+                {
+                    distances[edge.to] = distances[from] + edge.cost;
+                    incoming_edges[edge.to] = Some(edge_index);
+                }
+            }
+        }
+
+        if distances[sink] == i64::MAX {
+            return total_flow;
+        }
+
+        let mut path = vec![];
+        let mut node = sink;
+
+        while let Some(edge_index) = incoming_edges[node] {
+            path.push(edge_index);
+            node = edges[edge_index ^ 1].to;
+        }
+
+        let bottleneck = path
+            .iter()
+            .map(|edge_index| edges[*edge_index].capacity)
+            .min()
+            .unwrap();
+
+        for edge_index in path {
+            edges[edge_index].capacity -= bottleneck;
+            edges[edge_index ^ 1].capacity += bottleneck;
+        }
+
+        total_flow += bottleneck;
+    }
+}
+
+// transportation problem from opponent items to your items bounded by the uses limit
+fn get_optimal_counts_by_flow(
+    opponent_counts: &[usize; 3],
+    max_item_uses: usize,
+) -> Option<[[usize; 3]; 3]> {
+    let source = 0;
+    let sink = 7;
+    let mut edges: Vec<FlowEdge> = vec![];
+    let add_edge = |edges: &mut Vec<FlowEdge>, from: usize, to: usize, capacity, cost| {
+        edges.push(FlowEdge { to, capacity, cost });
+        edges.push(FlowEdge {
+            to: from,
+            capacity: 0,
+            cost: -cost,
+        });
+    };
+
+    for (opponent_index, opponent_count) in opponent_counts.iter().enumerate() {
+        add_edge(
+            &mut edges,
+            source,
+            1 + opponent_index,
+            *opponent_count as i64,
+            0,
+        );
+    }
+    for (opponent_index, opponent_item) in ITEMS.iter().enumerate() {
+        for (your_index, your_item) in ITEMS.iter().enumerate() {
+            let score = get_round_score_by_items(opponent_item, your_item);
+
+            add_edge(
+                &mut edges,
+                1 + opponent_index,
+                4 + your_index,
+                i64::MAX,
+                -score as i64,
+            );
+        }
+    }
+    for your_index in 0..3 {
+        add_edge(&mut edges, 4 + your_index, sink, max_item_uses as i64, 0);
+    }
+
+    let rounds_count: usize = opponent_counts.iter().sum();
+
+    if run_min_cost_flow(8, &mut edges, source, sink) < rounds_count as i64 {
+        return None;
+    }
+
+    let mut counts = [[0; 3]; 3];
+
+    for (opponent_index, opponent_row) in counts.iter_mut().enumerate() {
+        for (your_index, count) in opponent_row.iter_mut().enumerate() {
+            // reverse edge capacity equals the flow sent through the forward one
+            let edge_index = 6 + 2 * (3 * opponent_index + your_index);
+            *count = edges[edge_index + 1].capacity as usize;
+        }
+    }
 
-    let rounds: Vec<Vec<&str>> = content
+    return Some(counts);
+}
+
+// with losses fixed per opponent item, draws against each item are the only free choice,
+// against item i the draw uses item i, the win item i + 1 and the loss item i + 2 (mod 3)
+fn get_min_draws(
+    opponent_counts: &[usize; 3],
+    losses: &[usize; 3],
+    max_item_uses: i64,
+) -> Option<[usize; 3]> {
+    let remaining: Vec<i64> = (0..3)
+        .map(|index| opponent_counts[index] as i64 - losses[index] as i64)
+        .collect();
+    // item i used as draw + win + loss forces draws[i - 1] >= draws[i] + excess[i]
+    let excess: Vec<i64> = (0..3)
+        .map(|index| remaining[(index + 2) % 3] + losses[(index + 1) % 3] as i64 - max_item_uses)
+        .collect();
+
+    let get_draws = |first_draws: i64| {
+        let third_draws = (first_draws + excess[0]).max(0);
+        let second_draws = (third_draws + excess[2]).max(0);
+
+        return [first_draws, second_draws, third_draws];
+    };
+
+    // draws are never cheaper than wins, so the smallest first count closing the cycle wins
+    let mut low = 0;
+    let mut high = remaining[0] + 1;
+
+    while low < high {
+        let middle = (low + high) / 2;
+        let draws = get_draws(middle);
+
+        if draws[0] >= draws[1] + excess[1] {
+            high = middle;
+        } else {
+            low = middle + 1;
+        }
+    }
+
+    if low > remaining[0] {
+        return None;
+    }
+
+    let draws = get_draws(low);
+
+    if draws[1] > remaining[1] || draws[2] > remaining[2] {
+        return None;
+    }
+
+    return Some(draws.map(|draw| draw as usize));
+}
+
+fn get_optimal_counts_by_losses(
+    opponent_counts: &[usize; 3],
+    required_losses: usize,
+    max_item_uses: usize,
+) -> Option<[[usize; 3]; 3]> {
+    let mut best: Option<(i32, [[usize; 3]; 3])> = None;
+
+    for first_losses in 0..=required_losses.min(opponent_counts[0]) {
+        for second_losses in 0..=(required_losses - first_losses).min(opponent_counts[1]) {
+            let third_losses = required_losses - first_losses - second_losses;
+
+            if third_losses > opponent_counts[2] {
+                continue;
+            }
+
+            let losses = [first_losses, second_losses, third_losses];
+
+            if let Some(draws) = get_min_draws(opponent_counts, &losses, max_item_uses as i64) {
+                let mut counts = [[0; 3]; 3];
+
+                for index in 0..3 {
+                    counts[index][index] = draws[index];
+                    counts[index][(index + 1) % 3] =
+                        opponent_counts[index] - losses[index] - draws[index];
+                    counts[index][(index + 2) % 3] = losses[index];
+                }
+
+                let score = get_counts_score(&counts);
+
+                if best.is_none() || score > best.unwrap().0 {
+                    best = Some((score, counts));
+                }
+            }
+        }
+    }
+
+    return best.map(|(score, counts)| counts);
+}
+
+fn get_counts_score(counts: &[[usize; 3]; 3]) -> i32 {
+    let mut score = 0;
+
+    for opponent_index in 0..3 {
+        for your_index in 0..3 {
+            score += counts[opponent_index][your_index] as i32
+                * get_round_score_by_items(&ITEMS[opponent_index], &ITEMS[your_index]);
+        }
+    }
+
+    return score;
+}
+
+// rounds are independent, so only the amount of each (opponent, you) pair matters
+fn find_optimal_strategy(
+    opponent_items: &Vec<Item>,
+    constraints: &StrategyConstraints,
+) -> Option<OptimalStrategy> {
+    let mut opponent_counts = [0; 3];
+
+    for item in opponent_items {
+        opponent_counts[get_item_index(item)] += 1;
+    }
+
+    let max_item_uses = constraints.max_item_uses.unwrap_or(opponent_items.len());
+
+    let counts = match constraints.required_losses {
+        Some(required_losses) => {
+            get_optimal_counts_by_losses(&opponent_counts, required_losses, max_item_uses)
+        }
+        None => get_optimal_counts_by_flow(&opponent_counts, max_item_uses),
+    }?;
+
+    let mut remaining_counts = counts;
+    let your_items = opponent_items
+        .iter()
+        .map(|opponent_item| {
+            let opponent_index = get_item_index(opponent_item);
+            let your_index = (0..3)
+                .find(|index| remaining_counts[opponent_index][*index] > 0)
+                .unwrap();
+
+            remaining_counts[opponent_index][your_index] -= 1;
+
+            ITEMS[your_index]
+        })
+        .collect();
+
+    return Some(OptimalStrategy {
+        score: get_counts_score(&counts),
+        your_items,
+    });
+}
+
+fn get_rounds(content: &str) -> Vec<Vec<&str>> {
+    return content
         .split("\n")
         .map(|round| round.split(" ").collect())
         .collect();
+}
+
+pub fn execute() {
+    let content = fs::read_to_string(FILE_PATH).expect("Should have been able to read the file");
+
+    let rounds = get_rounds(&content);
 
     let result_1: i32 = rounds.iter().map(get_round_score_1).sum();
     let result_2: i32 = rounds.iter().map(get_round_score_2).sum();
@@ -129,3 +690,220 @@ pub fn execute() {
     println!("Result 1 {}", result_1);
     println!("Result 2 {}", result_2);
 }
+
+pub fn execute_mapping_analysis() {
+    let content = fs::read_to_string(FILE_PATH).expect("Should have been able to read the file");
+
+    let rounds = get_rounds(&content);
+
+    for (index, round) in rounds.iter().enumerate() {
+        if get_guide_round(round).is_none() {
+            println!("Round {} skipped: {}", index + 1, round.join(" "));
+        }
+    }
+
+    for (rank, mapping_score) in rank_column_mappings(&rounds).iter().enumerate() {
+        println!(
+            "{}. {} -> {}",
+            rank + 1,
+            mapping_score.mapping.describe(),
+            mapping_score.score
+        );
+    }
+}
+
+// part is "1" or "2" and selects how the second column is read, format is "table" or "json"
+pub fn execute_tournament_report(part: &str, format: &str) {
+    let content = fs::read_to_string(FILE_PATH).expect("Should have been able to read the file");
+
+    let wanted_result_column = match part {
+        "1" => false,
+        "2" => true,
+        _ => {
+            println!("Unknown part {}", part);
+
+            return;
+        }
+    };
+
+    if !["table", "json"].contains(&format) {
+        println!("Unknown format {}", format);
+
+        return;
+    }
+
+    let rounds = get_rounds(&content);
+    let report = build_tournament_report(&rounds, wanted_result_column);
+
+    match format {
+        "json" => println!("{}", report.to_json()),
+        _ => println!("{}", report.to_table()),
+    }
+}
+
+// options are "--max-uses K" limiting each item and "--losses L" requiring exact losses
+pub fn execute_optimal_strategy(options: &[&str]) {
+    let content = fs::read_to_string(FILE_PATH).expect("Should have been able to read the file");
+
+    let mut constraints = StrategyConstraints {
+        max_item_uses: None,
+        required_losses: None,
+    };
+    let mut options_iter = options.iter();
+
+    while let Some(option) = options_iter.next() {
+        let value = options_iter
+            .next()
+            .and_then(|value| value.parse::<usize>().ok());
+
+        match (*option, value) {
+            ("--max-uses", Some(value)) => constraints.max_item_uses = Some(value),
+            ("--losses", Some(value)) => constraints.required_losses = Some(value),
+            ("--max-uses" | "--losses", None) => {
+                println!("Option {} needs a non negative number", option);
+
+                return;
+            }
+            _ => {
+                println!("Unknown option {}", option);
+
+                return;
+            }
+        }
+    }
+
+    let mut opponent_items = vec![];
+
+    for (index, round) in get_rounds(&content).iter().enumerate() {
+        if is_blank_round(round) {
+            continue;
+        }
+
+        match get_opponent_item(round) {
+            Some(opponent_item) => opponent_items.push(opponent_item),
+            None => println!("Round {} skipped: {}", index + 1, round.join(" ")),
+        }
+    }
+
+    match find_optimal_strategy(&opponent_items, &constraints) {
+        Some(strategy) => {
+            let moves: Vec<String> = strategy
+                .your_items
+                .iter()
+                .map(|item| format!("{:?}", item))
+                .collect();
+
+            println!("Moves {}", moves.join(" "));
+            println!("Result {}", strategy.score);
+        }
+        None => println!("No strategy satisfies the constraints"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::random::Random;
+
+    fn is_loss(opponent_item: &Item, your_item: &Item) -> bool {
+        return matches!(check_result(your_item, opponent_item), Result::Lose);
+    }
+
+    // best score over every possible sequence of your items satisfying the constraints
+    fn find_best_score_exhaustively(
+        opponent_items: &Vec<Item>,
+        constraints: &StrategyConstraints,
+    ) -> Option<i32> {
+        let rounds_count = opponent_items.len();
+        let mut best_score = None;
+
+        for mut code in 0..3_usize.pow(rounds_count as u32) {
+            let mut uses = [0; 3];
+            let mut losses = 0;
+            let mut score = 0;
+
+            for opponent_item in opponent_items {
+                let your_item = ITEMS[code % 3];
+                code /= 3;
+
+                uses[get_item_index(&your_item)] += 1;
+                if is_loss(opponent_item, &your_item) {
+                    losses += 1;
+                }
+                score += get_round_score_by_items(opponent_item, &your_item);
+            }
+
+            let within_uses = constraints
+                .max_item_uses
+                .is_none_or(|max_item_uses| uses.iter().all(|uses| *uses <= max_item_uses));
+            let exact_losses = constraints
+                .required_losses
+                .is_none_or(|required_losses| losses == required_losses);
+
+            if within_uses && exact_losses {
+                best_score = best_score.max(Some(score));
+            }
+        }
+
+        return best_score;
+    }
+
+    fn check_strategy(opponent_items: &Vec<Item>, constraints: &StrategyConstraints) {
+        let strategy = find_optimal_strategy(opponent_items, constraints);
+
+        assert_eq!(
+            strategy.as_ref().map(|strategy| strategy.score),
+            find_best_score_exhaustively(opponent_items, constraints)
+        );
+
+        if let Some(strategy) = strategy {
+            let mut uses = [0; 3];
+            let mut losses = 0;
+            let mut score = 0;
+
+            for (opponent_item, your_item) in opponent_items.iter().zip(&strategy.your_items) {
+                uses[get_item_index(your_item)] += 1;
+                if is_loss(opponent_item, your_item) {
+                    losses += 1;
+                }
+                score += get_round_score_by_items(opponent_item, your_item);
+            }
+
+            assert_eq!(strategy.your_items.len(), opponent_items.len());
+            assert_eq!(score, strategy.score);
+            if let Some(max_item_uses) = constraints.max_item_uses {
+                assert!(uses.iter().all(|uses| *uses <= max_item_uses));
+            }
+            if let Some(required_losses) = constraints.required_losses {
+                assert_eq!(losses, required_losses);
+            }
+        }
+    }
+
+    #[test]
+    fn matches_exhaustive_search_on_small_guides() {
+        let mut random = Random::new(2022);
+
+        for _ in 0..500 {
+            let rounds_count = random.next_below(7);
+            let opponent_items: Vec<Item> = (0..rounds_count)
+                .map(|_| ITEMS[random.next_below(3)])
+                .collect();
+
+            let max_item_uses_options = [None, Some(0), Some(random.next_below(rounds_count + 1))];
+            let required_losses_options = [None, Some(random.next_below(rounds_count + 2))];
+
+            for max_item_uses in max_item_uses_options {
+                for required_losses in required_losses_options {
+                    check_strategy(
+                        &opponent_items,
+                        &StrategyConstraints {
+                            max_item_uses,
+                            required_losses,
+                        },
+                    );
+                }
+            }
+        }
+    }
+}
@@ -1,81 +1,417 @@
 #![allow(dead_code)]
 #![allow(unused_variables)]
-use std::collections::HashSet;
-use std::fs;
+use crate::common::item_set::{ItemSet, MAX_ITEMS};
+use std::{collections::HashMap, fmt, fs};
 
 const FILE_PATH: &str = "src/day_03/resources/input.txt";
+const COMPARTMENTS_COUNT: usize = 2;
+const GROUP_SIZE: usize = 3;
+const INVALID_LAYOUT_MESSAGE: &str =
+    "Compartments count and group size have to be positive numbers";
 
-fn get_letter_value(letter: char) -> u32 {
-    if letter.is_ascii_uppercase() {
-        return letter as u32 - 38;
-    }
-    if letter.is_ascii_lowercase() {
-        return letter as u32 - 96;
+const DEFAULT_ALPHABET: &str = "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+#[derive(Debug)]
+enum PrioritySchemeError {
+    DuplicateItem(char),
+    TooManyItems(usize),
+    MalformedLine(usize),
+    UnreadableFile(String),
+    InvalidOptions(String),
+}
+
+impl fmt::Display for PrioritySchemeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PrioritySchemeError::DuplicateItem(item) => write!(f, "item {} defined twice", item),
+            PrioritySchemeError::TooManyItems(items_count) => write!(
+                f,
+                "{} items defined, at most {} are supported",
+                items_count, MAX_ITEMS
+            ),
+            PrioritySchemeError::MalformedLine(line_number) => {
+                write!(f, "malformed priority table line {}", line_number)
+            }
+            PrioritySchemeError::UnreadableFile(path) => write!(f, "can not read {}", path),
+            PrioritySchemeError::InvalidOptions(options) => write!(
+                f,
+                "expected \"--alphabet ITEMS\" or \"--priorities FILE\", got \"{}\"",
+                options
+            ),
+        }
     }
+}
 
-    return 0;
+// maps items to priorities and to dense ItemSet indices, so items sharing a priority stay
+// distinct when intersected
+struct PriorityScheme {
+    priorities: HashMap<char, u32>,
+    indices: HashMap<char, usize>,
+    items: Vec<char>,
 }
 
-fn get_rucksack_common_items_price(comparments: &Vec<String>) -> u32 {
-    let common_items = comparments.iter().map(|s| s.to_string()).fold(
-        HashSet::new(),
-        |acc: HashSet<u32>, cur: String| {
-            let nxt_values: HashSet<u32> = cur.chars().map(get_letter_value).collect();
+impl PriorityScheme {
+    fn from_pairs(
+        pairs: impl IntoIterator<Item = (char, u32)>,
+    ) -> Result<PriorityScheme, PrioritySchemeError> {
+        let mut priority_scheme = PriorityScheme {
+            priorities: HashMap::new(),
+            indices: HashMap::new(),
+            items: vec![],
+        };
+
+        for (item, priority) in pairs {
+            if priority_scheme.priorities.insert(item, priority).is_some() {
+                return Err(PrioritySchemeError::DuplicateItem(item));
+            }
+
+            priority_scheme
+                .indices
+                .insert(item, priority_scheme.items.len());
+            priority_scheme.items.push(item);
+        }
+
+        if priority_scheme.items.len() > MAX_ITEMS {
+            return Err(PrioritySchemeError::TooManyItems(
+                priority_scheme.items.len(),
+            ));
+        }
+
+        return Ok(priority_scheme);
+    }
+
+    // items are worth their position in the alphabet starting from 1
+    fn from_alphabet(alphabet: &str) -> Result<PriorityScheme, PrioritySchemeError> {
+        return PriorityScheme::from_pairs(
+            alphabet
+                .chars()
+                .enumerate()
+                .map(|(index, item)| (item, index as u32 + 1)),
+        );
+    }
 
-            let intersected = acc
-                .intersection(&nxt_values)
-                .map(|i| *i)
-                .collect::<HashSet<_>>();
+    // every non empty line holds an item and its priority separated by whitespace
+    fn from_table(table: &str) -> Result<PriorityScheme, PrioritySchemeError> {
+        let mut pairs = vec![];
 
-            if intersected.is_empty() {
-                return nxt_values;
+        for (index, line) in table.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
             }
 
-            return intersected;
-        },
-    );
+            let pair = match line.split_whitespace().collect::<Vec<&str>>()[..] {
+                [item, priority] if item.chars().count() == 1 => priority
+                    .parse::<u32>()
+                    .ok()
+                    .map(|priority| (item.chars().next().unwrap(), priority)),
+                _ => None,
+            };
+
+            pairs.push(pair.ok_or(PrioritySchemeError::MalformedLine(index + 1))?);
+        }
+
+        return PriorityScheme::from_pairs(pairs);
+    }
 
-    return common_items.iter().sum();
+    fn get_priority(&self, item: char) -> Result<u32, RucksackError> {
+        return self
+            .priorities
+            .get(&item)
+            .copied()
+            .ok_or(RucksackError::UnknownItem(item));
+    }
+
+    fn get_index(&self, item: char) -> Result<usize, RucksackError> {
+        return self
+            .indices
+            .get(&item)
+            .copied()
+            .ok_or(RucksackError::UnknownItem(item));
+    }
+
+    fn get_item(&self, index: usize) -> char {
+        return self.items[index];
+    }
 }
 
-pub fn execute() {
-    let content = fs::read_to_string(FILE_PATH).expect("Should have been able to read the file");
+#[derive(Clone, Debug)]
+enum RucksackError {
+    NoCommonItem,
+    MultipleCommonItems(Vec<char>),
+    EmptyRucksack,
+    UnevenCompartments {
+        length: usize,
+        compartments_count: usize,
+    },
+    IncompleteGroup {
+        size: usize,
+        group_size: usize,
+    },
+    UnknownItem(char),
+}
+
+impl fmt::Display for RucksackError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RucksackError::NoCommonItem => write!(f, "no common item"),
+            RucksackError::MultipleCommonItems(items) => write!(
+                f,
+                "multiple common items {}",
+                items.iter().collect::<String>()
+            ),
+            RucksackError::EmptyRucksack => write!(f, "empty rucksack"),
+            RucksackError::UnevenCompartments {
+                length,
+                compartments_count,
+            } => write!(
+                f,
+                "{} items can not be split into {} compartments",
+                length, compartments_count
+            ),
+            RucksackError::IncompleteGroup { size, group_size } => write!(
+                f,
+                "incomplete group of {} rucksacks, expected {}",
+                size, group_size
+            ),
+            RucksackError::UnknownItem(item) => write!(f, "unknown item {}", item),
+        }
+    }
+}
+
+fn get_items_set(items: &str, priority_scheme: &PriorityScheme) -> Result<ItemSet, RucksackError> {
+    let mut items_set = ItemSet::new();
+
+    for item in items.chars() {
+        items_set.insert(priority_scheme.get_index(item)?);
+    }
+
+    return Ok(items_set);
+}
+
+// the one item type shared by all compartments of a rucksack or all rucksacks of a group
+fn get_common_item(
+    comparments: &Vec<String>,
+    priority_scheme: &PriorityScheme,
+) -> Result<char, RucksackError> {
+    let mut common_items = ItemSet::full(priority_scheme.items.len());
+
+    for compartment in comparments {
+        common_items = common_items & get_items_set(compartment, priority_scheme)?;
+    }
+
+    let common_letters: Vec<char> = common_items
+        .indices()
+        .map(|index| priority_scheme.get_item(index))
+        .collect();
+
+    match common_letters[..] {
+        [] => Err(RucksackError::NoCommonItem),
+        [common_letter] => Ok(common_letter),
+        _ => Err(RucksackError::MultipleCommonItems(common_letters)),
+    }
+}
 
-    let rucksacks: Vec<Vec<char>> = content
+fn get_rucksack_common_items_price(
+    comparments: &Vec<String>,
+    priority_scheme: &PriorityScheme,
+) -> Result<u32, RucksackError> {
+    return get_common_item(comparments, priority_scheme)
+        .and_then(|common_item| priority_scheme.get_priority(common_item));
+}
+
+// invalid rucksacks or groups are reported and left out of the sum
+fn get_common_items_prices_sum(
+    groups: &Vec<Result<Vec<String>, RucksackError>>,
+    group_name: &str,
+    priority_scheme: &PriorityScheme,
+) -> u32 {
+    return groups.iter().enumerate().fold(0, |acc, (index, group)| {
+        let price = match group {
+            Ok(group) => get_rucksack_common_items_price(group, priority_scheme),
+            Err(error) => Err(error.clone()),
+        };
+
+        match price {
+            Ok(price) => acc + price,
+            Err(error) => {
+                println!("{} {}: {}", group_name, index + 1, error);
+
+                acc
+            }
+        }
+    });
+}
+
+fn get_rucksacks(content: &str) -> Vec<Vec<char>> {
+    return content
         .split("\n")
         .map(|rucksack| rucksack.chars().collect())
         .collect();
+}
 
-    let rucksacks_compartments = rucksacks
+fn get_rucksacks_compartments(
+    rucksacks: &Vec<Vec<char>>,
+    compartments_count: usize,
+) -> Vec<Result<Vec<String>, RucksackError>> {
+    return rucksacks
         .iter()
         .map(|rucksack| {
-            rucksack
-                .chunks(rucksack.len() / 2)
+            if rucksack.is_empty() {
+                return Err(RucksackError::EmptyRucksack);
+            }
+
+            if rucksack.len() % compartments_count != 0 {
+                return Err(RucksackError::UnevenCompartments {
+                    length: rucksack.len(),
+                    compartments_count,
+                });
+            }
+
+            Ok(rucksack
+                .chunks(rucksack.len() / compartments_count)
                 .map(|compartment| compartment.iter().collect::<String>())
-                .collect::<Vec<_>>()
+                .collect::<Vec<_>>())
         })
         .collect::<Vec<_>>();
+}
 
-    let elfs_groups = rucksacks
-        .chunks(3)
+fn get_elfs_groups(
+    rucksacks: &Vec<Vec<char>>,
+    group_size: usize,
+) -> Vec<Result<Vec<String>, RucksackError>> {
+    return rucksacks
+        .chunks(group_size)
         .map(|rucksack| {
-            rucksack
+            if rucksack.len() < group_size {
+                return Err(RucksackError::IncompleteGroup {
+                    size: rucksack.len(),
+                    group_size,
+                });
+            }
+
+            Ok(rucksack
                 .iter()
                 .map(|item| item.iter().collect::<String>())
-                .collect::<Vec<_>>()
+                .collect::<Vec<_>>())
         })
         .collect::<Vec<_>>();
+}
 
-    let result_1: u32 = rucksacks_compartments
-        .iter()
-        .map(get_rucksack_common_items_price)
-        .sum();
+// "--alphabet ITEMS" or "--priorities FILE" replace the default letters priorities, anything
+// else is rejected rather than silently ignored
+fn get_priority_scheme(options: &[&str]) -> Result<PriorityScheme, PrioritySchemeError> {
+    match options {
+        [] => PriorityScheme::from_alphabet(DEFAULT_ALPHABET),
+        ["--alphabet", alphabet] => PriorityScheme::from_alphabet(alphabet),
+        ["--priorities", path] => fs::read_to_string(path)
+            .map_err(|_| PrioritySchemeError::UnreadableFile(path.to_string()))
+            .and_then(|table| PriorityScheme::from_table(&table)),
+        _ => Err(PrioritySchemeError::InvalidOptions(options.join(" "))),
+    }
+}
 
-    let result_2: u32 = elfs_groups
-        .iter()
-        .map(get_rucksack_common_items_price)
-        .sum();
+pub fn execute() {
+    execute_with_sizes(COMPARTMENTS_COUNT, GROUP_SIZE, &[]);
+}
+
+// compartments count and group size as given on the command line, both have to be positive
+fn get_layout(compartments_count: &str, group_size: &str) -> Option<(usize, usize)> {
+    let get_size = |value: &str| value.parse::<usize>().ok().filter(|size| *size > 0);
+
+    return get_size(compartments_count).zip(get_size(group_size));
+}
+
+pub fn execute_with_layout(compartments_count: &str, group_size: &str, options: &[&str]) {
+    match get_layout(compartments_count, group_size) {
+        Some((compartments_count, group_size)) => {
+            execute_with_sizes(compartments_count, group_size, options)
+        }
+        None => println!("{}", INVALID_LAYOUT_MESSAGE),
+    }
+}
+
+fn execute_with_sizes(compartments_count: usize, group_size: usize, options: &[&str]) {
+    let priority_scheme = match get_priority_scheme(options) {
+        Ok(priority_scheme) => priority_scheme,
+        Err(error) => {
+            println!("Invalid priorities: {}", error);
+
+            return;
+        }
+    };
+
+    let content = fs::read_to_string(FILE_PATH).expect("Should have been able to read the file");
+
+    let rucksacks = get_rucksacks(&content);
+
+    let rucksacks_compartments = get_rucksacks_compartments(&rucksacks, compartments_count);
+    let elfs_groups = get_elfs_groups(&rucksacks, group_size);
+
+    let result_1 =
+        get_common_items_prices_sum(&rucksacks_compartments, "Rucksack", &priority_scheme);
+    let result_2 = get_common_items_prices_sum(&elfs_groups, "Group", &priority_scheme);
 
     println!("Result 1 {}", result_1);
     println!("Result 2 {}", result_2);
 }
+
+fn print_common_items(
+    groups: &Vec<Result<Vec<String>, RucksackError>>,
+    group_name: &str,
+    priority_scheme: &PriorityScheme,
+) {
+    for (index, group) in groups.iter().enumerate() {
+        let common_item = match group {
+            Ok(group) => get_common_item(group, priority_scheme),
+            Err(error) => Err(error.clone()),
+        };
+
+        match common_item {
+            Ok(common_item) => println!("{} {} {}", group_name, index + 1, common_item),
+            Err(error) => println!("{} {}: {}", group_name, index + 1, error),
+        }
+    }
+}
+
+pub fn execute_common_items(options: &[&str]) {
+    print_common_items_with_sizes(COMPARTMENTS_COUNT, GROUP_SIZE, options);
+}
+
+pub fn execute_common_items_with_layout(
+    compartments_count: &str,
+    group_size: &str,
+    options: &[&str],
+) {
+    match get_layout(compartments_count, group_size) {
+        Some((compartments_count, group_size)) => {
+            print_common_items_with_sizes(compartments_count, group_size, options)
+        }
+        None => println!("{}", INVALID_LAYOUT_MESSAGE),
+    }
+}
+
+fn print_common_items_with_sizes(compartments_count: usize, group_size: usize, options: &[&str]) {
+    let priority_scheme = match get_priority_scheme(options) {
+        Ok(priority_scheme) => priority_scheme,
+        Err(error) => {
+            println!("Invalid priorities: {}", error);
+
+            return;
+        }
+    };
+
+    let content = fs::read_to_string(FILE_PATH).expect("Should have been able to read the file");
+
+    let rucksacks = get_rucksacks(&content);
+
+    print_common_items(
+        &get_rucksacks_compartments(&rucksacks, compartments_count),
+        "Rucksack",
+        &priority_scheme,
+    );
+    print_common_items(
+        &get_elfs_groups(&rucksacks, group_size),
+        "Group",
+        &priority_scheme,
+    );
+}
@@ -1,83 +1,357 @@
 #![allow(dead_code)]
 #![allow(unused_variables)]
-use std::fs;
-use std::ops::Range;
+use crate::common::interval::{get_coverage_depths, merge_intervals, Interval};
+use crate::common::interval_index::IntervalIndex;
+use std::{fmt, fs};
 
 const FILE_PATH: &str = "src/day_04/resources/input.txt";
 
-fn create_pair_item_range(item: &str) -> Range<i32> {
-    let indices: Vec<i32> = item
+#[derive(Debug)]
+struct InvalidRange {
+    line_number: usize,
+    range: String,
+}
+
+impl fmt::Display for InvalidRange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "invalid range {} at line {}",
+            self.range, self.line_number
+        )
+    }
+}
+
+fn create_group_item_range(item: &str) -> Option<Interval> {
+    let indices = item
         .split("-")
-        .map(|index| index.parse().unwrap())
-        .collect();
+        .map(|index| index.parse().ok())
+        .collect::<Option<Vec<i64>>>()?;
 
+    // reversed bounds do not describe any sections
     if let [start_index, end_index] = indices[..] {
-        return Range {
-            start: start_index,
-            end: end_index + 1,
-        };
+        if start_index <= end_index {
+            return Some(Interval::new(start_index, end_index));
+        }
+    }
+
+    return None;
+}
+
+// index of a range containing all other ranges of the group, a lone range has nothing to contain
+fn get_containing_range_index(ranges: &Vec<Interval>) -> Option<usize> {
+    if ranges.len() < 2 {
+        return None;
+    }
+
+    let start = ranges.iter().map(|range| range.start).min()?;
+    let end = ranges.iter().map(|range| range.end).max()?;
+
+    return ranges
+        .iter()
+        .position(|range| range.start == start && range.end == end);
+}
+
+// sections assigned to every elf of the group, a lone range overlaps with nobody
+fn get_common_sections(ranges: &Vec<Interval>) -> Option<Interval> {
+    if ranges.len() < 2 {
+        return None;
+    }
+
+    let (first_range, other_ranges) = ranges.split_first()?;
+
+    return other_ranges
+        .iter()
+        .try_fold(*first_range, |acc, cur| acc.intersection(cur));
+}
+
+fn get_overlaps_matrix(ranges: &Vec<Interval>) -> Vec<Vec<i64>> {
+    return ranges
+        .iter()
+        .map(|first_range| {
+            ranges
+                .iter()
+                .map(|second_range| {
+                    first_range
+                        .intersection(second_range)
+                        .map_or(0, |intersection| intersection.len())
+                })
+                .collect()
+        })
+        .collect();
+}
+
+fn check_group_whole_subrange_reducer(acc: i32, cur: &Vec<Interval>) -> i32 {
+    if get_containing_range_index(cur).is_some() {
+        return acc + 1;
     }
 
-    return Range { start: 0, end: 0 };
+    return acc;
 }
 
-fn check_pair_whole_subrange_reducer(acc: i32, cur: &Vec<Range<i32>>) -> i32 {
-    if let [first_range, second_range] = &cur[..] {
-        if &first_range.len() > &second_range.len() {
-            let are_boundaries_in_range = second_range.clone().min() >= first_range.clone().min()
-                && second_range.clone().max() <= first_range.clone().max();
+fn check_group_partial_subrange_reducer(acc: i32, cur: &Vec<Interval>) -> i32 {
+    if get_common_sections(cur).is_some() {
+        return acc + 1;
+    }
+
+    return acc;
+}
 
-            if are_boundaries_in_range {
-                return acc + 1;
+// empty lines are groups without elves, any other malformed or reversed range is an error
+fn get_groups_ranges(content: &str) -> Result<Vec<Vec<Interval>>, InvalidRange> {
+    return content
+        .split("\n")
+        .enumerate()
+        .map(|(index, group)| {
+            if group.is_empty() {
+                return Ok(vec![]);
             }
 
-            return acc;
+            group
+                .split(",")
+                .map(|item| {
+                    create_group_item_range(item).ok_or(InvalidRange {
+                        line_number: index + 1,
+                        range: item.to_string(),
+                    })
+                })
+                .collect()
+        })
+        .collect();
+}
+
+fn read_groups_ranges() -> Option<Vec<Vec<Interval>>> {
+    let content = fs::read_to_string(FILE_PATH).expect("Should have been able to read the file");
+
+    match get_groups_ranges(&content) {
+        Ok(groups_ranges) => Some(groups_ranges),
+        Err(error) => {
+            println!("Invalid input: {}", error);
+
+            None
         }
+    }
+}
+
+pub fn execute() {
+    let groups_ranges = match read_groups_ranges() {
+        Some(groups_ranges) => groups_ranges,
+        None => return,
+    };
+
+    let result_1 = groups_ranges
+        .iter()
+        .fold(0, check_group_whole_subrange_reducer);
+    let result_2 = groups_ranges
+        .iter()
+        .fold(0, check_group_partial_subrange_reducer);
+
+    println!("Result 1 {}", { result_1 });
+    println!("Result 2 {}", { result_2 });
+}
+
+struct ElfAssignment {
+    group_number: usize,
+    elf_number: usize,
+    range: Interval,
+}
+
+struct CoverageReport {
+    coverage: Vec<Interval>,
+    gaps: Vec<Interval>,
+    most_covered_sections: Vec<Interval>,
+    max_elves_count: usize,
+    redundant_elves: Vec<(usize, usize)>,
+    groups_common_sections: Vec<i64>,
+}
+
+impl CoverageReport {
+    fn to_text(&self) -> String {
+        let join = |intervals: &Vec<Interval>| {
+            intervals
+                .iter()
+                .map(|interval| interval.to_string())
+                .collect::<Vec<String>>()
+                .join(", ")
+        };
 
-        let are_boundaries_in_range = first_range.clone().min() >= second_range.clone().min()
-            && first_range.clone().max() <= second_range.clone().max();
+        let mut text = format!("Coverage {}\n", join(&self.coverage));
+        text.push_str(&format!("Gaps {}\n", join(&self.gaps)));
+        text.push_str(&format!(
+            "Most covered sections {} by {} elves\n",
+            join(&self.most_covered_sections),
+            self.max_elves_count
+        ));
+        text.push_str(&format!(
+            "Redundant elves {}\n",
+            self.redundant_elves
+                .iter()
+                .map(|(group_number, elf_number)| format!("{}/{}", group_number, elf_number))
+                .collect::<Vec<String>>()
+                .join(", ")
+        ));
 
-        if are_boundaries_in_range {
-            return acc + 1;
+        for (index, common_sections) in self.groups_common_sections.iter().enumerate() {
+            text.push_str(&format!(
+                "Group {} common sections {}\n",
+                index + 1,
+                common_sections
+            ));
         }
 
-        return acc;
+        return text;
     }
+}
 
-    return acc;
+fn get_elves_assignments(groups_ranges: &Vec<Vec<Interval>>) -> Vec<ElfAssignment> {
+    return groups_ranges
+        .iter()
+        .enumerate()
+        .flat_map(|(group_index, group)| {
+            group
+                .iter()
+                .enumerate()
+                .map(move |(elf_index, range)| ElfAssignment {
+                    group_number: group_index + 1,
+                    elf_number: elf_index + 1,
+                    range: *range,
+                })
+        })
+        .collect();
 }
 
-fn check_pair_partial_subrange_reducer(acc: i32, cur: &Vec<Range<i32>>) -> i32 {
-    if let [first_range, second_range] = &cur[..] {
-        let is_any_element_contained = first_range
-            .clone()
-            .any(|item| second_range.clone().contains(&item));
+// elf is redundant when every of its sections is covered by at least one other elf
+fn is_elf_redundant(range: &Interval, depths: &Vec<(Interval, usize)>) -> bool {
+    let first_depth_index = depths.partition_point(|(interval, _)| interval.end < range.start);
+
+    let mut covered_until = range.start - 1;
 
-        if is_any_element_contained {
-            return acc + 1;
+    for (interval, depth) in &depths[first_depth_index..] {
+        if interval.start > range.end {
+            break;
+        }
+        if *depth < 2 || interval.start > covered_until + 1 {
+            return false;
         }
 
-        return acc;
+        covered_until = interval.end;
     }
 
-    return acc;
+    return covered_until >= range.end;
 }
 
-pub fn execute() {
-    let content = fs::read_to_string(FILE_PATH).expect("Should have been able to read the file");
+fn build_coverage_report(groups_ranges: &Vec<Vec<Interval>>) -> CoverageReport {
+    let assignments = get_elves_assignments(groups_ranges);
+    let ranges: Vec<Interval> = assignments
+        .iter()
+        .map(|assignment| assignment.range)
+        .collect();
 
-    let pairs_ranges: Vec<Vec<Range<i32>>> = content
-        .split("\n")
-        .map(|pair| pair.split(",").map(create_pair_item_range).collect())
+    let coverage = merge_intervals(&ranges);
+    let gaps = coverage
+        .windows(2)
+        .map(|neighbours| Interval::new(neighbours[0].end + 1, neighbours[1].start - 1))
         .collect();
 
-    let result_1 = pairs_ranges
+    let depths = get_coverage_depths(&ranges);
+    let max_elves_count = depths.iter().map(|(_, depth)| *depth).max().unwrap_or(0);
+    let most_covered_sections = merge_intervals(
+        &depths
+            .iter()
+            .filter(|(_, depth)| *depth == max_elves_count)
+            .map(|(interval, _)| *interval)
+            .collect::<Vec<Interval>>(),
+    );
+
+    let redundant_elves = assignments
         .iter()
-        .fold(0, check_pair_whole_subrange_reducer);
-    let result_2 = pairs_ranges
+        .filter(|assignment| is_elf_redundant(&assignment.range, &depths))
+        .map(|assignment| (assignment.group_number, assignment.elf_number))
+        .collect();
+
+    let groups_common_sections = groups_ranges
         .iter()
-        .fold(0, check_pair_partial_subrange_reducer);
+        .map(|group| get_common_sections(group).map_or(0, |sections| sections.len()))
+        .collect();
 
-    println!("Result 1 {}", { result_1 });
-    println!("Result 2 {}", { result_2 });
+    return CoverageReport {
+        coverage,
+        gaps,
+        most_covered_sections,
+        max_elves_count,
+        redundant_elves,
+        groups_common_sections,
+    };
+}
+
+pub fn execute_coverage_report() {
+    let groups_ranges = match read_groups_ranges() {
+        Some(groups_ranges) => groups_ranges,
+        None => return,
+    };
+
+    print!("{}", build_coverage_report(&groups_ranges).to_text());
+}
+
+pub fn execute_overlaps_matrices() {
+    let groups_ranges = match read_groups_ranges() {
+        Some(groups_ranges) => groups_ranges,
+        None => return,
+    };
+
+    for (index, group) in groups_ranges.iter().enumerate() {
+        println!("Group {}", index + 1);
+
+        for row in get_overlaps_matrix(group) {
+            let cells: Vec<String> = row.iter().map(|cell| format!("{:>4}", cell)).collect();
+
+            println!("{}", cells.join(""));
+        }
+    }
+}
+
+fn build_assignments_index(groups_ranges: &Vec<Vec<Interval>>) -> IntervalIndex<ElfAssignment> {
+    return IntervalIndex::new(
+        get_elves_assignments(groups_ranges)
+            .into_iter()
+            .map(|assignment| (assignment.range, assignment))
+            .collect(),
+    );
+}
+
+// kind is "point", "overlap", "contains" or "within", target is a section or a range
+pub fn execute_assignments_query(kind: &str, target: &str) {
+    let groups_ranges = match read_groups_ranges() {
+        Some(groups_ranges) => groups_ranges,
+        None => return,
+    };
+
+    let index = build_assignments_index(&groups_ranges);
+
+    let found = match kind {
+        "point" => target
+            .parse::<i64>()
+            .ok()
+            .map(|section| index.find_containing_point(section)),
+        "overlap" => create_group_item_range(target).map(|range| index.find_overlapping(&range)),
+        "contains" => create_group_item_range(target).map(|range| index.find_containing(&range)),
+        "within" => create_group_item_range(target).map(|range| index.find_contained(&range)),
+        _ => None,
+    };
+
+    let found = match found {
+        Some(found) => found,
+        None => {
+            println!("Unknown query {} {}", kind, target);
+
+            return;
+        }
+    };
+
+    for assignment in found {
+        println!(
+            "Group {} elf {} {}",
+            assignment.group_number, assignment.elf_number, assignment.range
+        );
+    }
 }
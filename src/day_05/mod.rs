@@ -1,10 +1,12 @@
 #![allow(dead_code)]
 #![allow(unused_variables)]
+use crate::common::random::Random;
 use lazy_static::lazy_static;
 use regex::Regex;
-use std::{cell::Cell, fs, ops::Not};
+use std::{fmt, fs, ops::Not, thread, time::Duration};
 
 const FILE_PATH: &str = "src/day_05/resources/input.txt";
+const EMPTY_STACK_PLACEHOLDER: &str = "-";
 
 struct Instruction {
     amount: u32,
@@ -12,156 +14,834 @@ struct Instruction {
     dst: u32,
 }
 
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "move {} from {} to {}", self.amount, self.src, self.dst)
+    }
+}
+
+// crates are ordered from the bottom, so the top crate is the last one
+#[derive(Clone)]
 struct Stack {
     number: u32,
-    crates: Cell<Vec<char>>,
+    crates: Vec<String>,
 }
 
 impl Stack {
-    fn add_crates(&self, new_crates: Vec<char>) {
-        let mut mutable_crates = self.crates.take();
+    fn add_crates(&mut self, new_crates: Vec<String>) {
+        self.crates.extend(new_crates);
+    }
 
-        mutable_crates.splice(0..0, new_crates);
+    fn remove_crates(&mut self, amount: usize, pick_multiple: bool) -> Vec<String> {
+        let mut removed_crates = self.crates.split_off(self.crates.len() - amount);
 
-        self.crates.set(mutable_crates)
+        if pick_multiple.not() {
+            removed_crates.reverse();
+        }
+
+        return removed_crates;
     }
 
-    fn remove_crates(&self, amount: usize, pick_multiple: bool) -> Vec<char> {
-        let mut mutable_crates = self.crates.take();
+    fn get_top_crate(&self) -> Option<&str> {
+        return self.crates.last().map(|label| label.as_str());
+    }
+}
 
-        let mut removed_crates: Vec<char> = mutable_crates.drain(0..amount).collect();
+// instructions are numbered from 1, empty lines are skipped
+fn get_instructions_data(raw_instructions: &str) -> Result<Vec<Instruction>, DrawingError> {
+    lazy_static! {
+        static ref INSTRUCTION_PATTERN: Regex =
+            Regex::new(r"^move (\d+) from (\d+) to (\d+)$").unwrap();
+    }
 
-        if pick_multiple.not() {
-            removed_crates.reverse();
+    return raw_instructions
+        .split("\n")
+        .map(|instruction| instruction.trim_end())
+        .filter(|instruction| instruction.is_empty().not())
+        .enumerate()
+        .map(|(index, instruction)| {
+            let values = INSTRUCTION_PATTERN
+                .captures(instruction)
+                .and_then(|captures| {
+                    (1..=3)
+                        .map(|group| captures[group].parse::<u32>().ok())
+                        .collect::<Option<Vec<u32>>>()
+                });
+
+            match values.as_deref() {
+                Some(&[amount, src, dst]) => Ok(Instruction { amount, src, dst }),
+                _ => Err(DrawingError::MalformedInstruction {
+                    number: index + 1,
+                    instruction: instruction.to_string(),
+                }),
+            }
+        })
+        .collect();
+}
+
+// lines and columns are counted from 1 as in text editors
+#[derive(Debug)]
+enum DrawingError {
+    MissingSections,
+    MissingNumberRow,
+    InvalidStackNumber {
+        column: usize,
+        value: String,
+    },
+    UnorderedStackNumber {
+        column: usize,
+        number: u32,
+    },
+    UnclosedCrate {
+        line: usize,
+        column: usize,
+    },
+    EmptyCrateLabel {
+        line: usize,
+        column: usize,
+    },
+    UnexpectedCharacter {
+        line: usize,
+        column: usize,
+        character: char,
+    },
+    CrateWithoutStack {
+        line: usize,
+        column: usize,
+    },
+    DuplicateCrate {
+        line: usize,
+        column: usize,
+    },
+    FloatingCrate {
+        line: usize,
+        column: usize,
+    },
+    MalformedInstruction {
+        number: usize,
+        instruction: String,
+    },
+}
+
+impl fmt::Display for DrawingError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DrawingError::MissingSections => {
+                write!(
+                    f,
+                    "drawing and instructions have to be separated by an empty line"
+                )
+            }
+            DrawingError::MissingNumberRow => write!(f, "drawing has no stack numbers row"),
+            DrawingError::InvalidStackNumber { column, value } => {
+                write!(f, "invalid stack number {} at column {}", value, column)
+            }
+            DrawingError::UnorderedStackNumber { column, number } => write!(
+                f,
+                "stack number {} at column {} breaks numbering from 1",
+                number, column
+            ),
+            DrawingError::UnclosedCrate { line, column } => {
+                write!(f, "unclosed crate at line {} column {}", line, column)
+            }
+            DrawingError::EmptyCrateLabel { line, column } => {
+                write!(f, "empty crate label at line {} column {}", line, column)
+            }
+            DrawingError::UnexpectedCharacter {
+                line,
+                column,
+                character,
+            } => write!(
+                f,
+                "unexpected character {} at line {} column {}",
+                character, line, column
+            ),
+            DrawingError::CrateWithoutStack { line, column } => write!(
+                f,
+                "crate at line {} column {} is not above exactly one stack number",
+                line, column
+            ),
+            DrawingError::DuplicateCrate { line, column } => write!(
+                f,
+                "crate at line {} column {} shares its stack with another crate of the line",
+                line, column
+            ),
+            DrawingError::FloatingCrate { line, column } => write!(
+                f,
+                "crate at line {} column {} has nothing underneath",
+                line, column
+            ),
+            DrawingError::MalformedInstruction {
+                number,
+                instruction,
+            } => write!(f, "malformed instruction {}: {}", number, instruction),
         }
+    }
+}
 
-        self.crates.set(mutable_crates);
+// token of a drawing line with its first and last character column, counted from 0
+struct DrawingToken {
+    start: usize,
+    end: usize,
+    text: String,
+}
 
-        return removed_crates;
+fn get_number_row_tokens(number_row: &str) -> Vec<DrawingToken> {
+    let mut tokens: Vec<DrawingToken> = vec![];
+
+    for (column, character) in number_row.chars().enumerate() {
+        if character.is_whitespace() {
+            continue;
+        }
+
+        match tokens.last_mut() {
+            Some(token) if token.end + 1 == column => {
+                token.end = column;
+                token.text.push(character);
+            }
+            _ => tokens.push(DrawingToken {
+                start: column,
+                end: column,
+                text: character.to_string(),
+            }),
+        }
     }
 
-    fn get_top_crate(&self) -> char {
-        let local_crates = self.crates.take().clone();
+    return tokens;
+}
+
+// crates are written as [label], everything between brackets is the label
+fn get_crates_row_tokens(crates_row: &str, line: usize) -> Result<Vec<DrawingToken>, DrawingError> {
+    let characters: Vec<char> = crates_row.chars().collect();
+    let mut tokens = vec![];
+    let mut column = 0;
+
+    while column < characters.len() {
+        let character = characters[column];
+
+        if character.is_whitespace() {
+            column += 1;
+            continue;
+        }
+
+        if character != '[' {
+            return Err(DrawingError::UnexpectedCharacter {
+                line,
+                column: column + 1,
+                character,
+            });
+        }
+
+        let label_length = characters[column + 1..]
+            .iter()
+            .position(|character| *character == ']')
+            .ok_or(DrawingError::UnclosedCrate {
+                line,
+                column: column + 1,
+            })?;
 
-        let top_item = local_crates.first().unwrap().clone();
-        self.crates.set(local_crates);
+        if label_length == 0 {
+            return Err(DrawingError::EmptyCrateLabel {
+                line,
+                column: column + 1,
+            });
+        }
+
+        tokens.push(DrawingToken {
+            start: column,
+            end: column + label_length + 1,
+            text: characters[column + 1..column + 1 + label_length]
+                .iter()
+                .collect(),
+        });
 
-        return top_item;
+        column += label_length + 2;
     }
+
+    return Ok(tokens);
 }
 
-fn get_instructions_data(raw_instructions: &str) -> Vec<Instruction> {
-    let instructions: Vec<&str> = raw_instructions.split("\n").collect();
+// stack columns are taken from the numbers row, a crate belongs to the number below it
+fn get_stacks_data(raw_stacks: &str) -> Result<Vec<Stack>, DrawingError> {
+    let mut stacks_rows: Vec<&str> = raw_stacks.split("\n").collect();
+    let number_row = stacks_rows.pop().ok_or(DrawingError::MissingNumberRow)?;
+    let number_tokens = get_number_row_tokens(number_row);
 
-    lazy_static! {
-        static ref AMOUNT_PATTERN: Regex = Regex::new(r"move (\d+)").unwrap();
-        static ref SRC_PATTERN: Regex = Regex::new(r"from (\d+)").unwrap();
-        static ref DST_PATTERN: Regex = Regex::new(r"to (\d+)").unwrap();
+    if number_tokens.is_empty() {
+        return Err(DrawingError::MissingNumberRow);
     }
 
-    let instructions_data: Vec<Instruction> = instructions
-        .iter()
-        .map(|instruction| {
-            let amount_match = AMOUNT_PATTERN.captures(&instruction).unwrap();
-            let src_match = SRC_PATTERN.captures(&instruction).unwrap();
-            let dst_match = DST_PATTERN.captures(&instruction).unwrap();
-
-            return Instruction {
-                amount: amount_match
-                    .get(1)
-                    .map_or(0, |m| m.as_str().parse::<u32>().unwrap()),
-                src: src_match
-                    .get(1)
-                    .map_or(0, |m| m.as_str().parse::<u32>().unwrap()),
-                dst: dst_match
-                    .get(1)
-                    .map_or(0, |m| m.as_str().parse::<u32>().unwrap()),
+    let mut stacks: Vec<Stack> = vec![];
+
+    for token in &number_tokens {
+        let number = token
+            .text
+            .parse::<u32>()
+            .map_err(|_| DrawingError::InvalidStackNumber {
+                column: token.start + 1,
+                value: token.text.clone(),
+            })?;
+
+        if number as usize != stacks.len() + 1 {
+            return Err(DrawingError::UnorderedStackNumber {
+                column: token.start + 1,
+                number,
+            });
+        }
+
+        stacks.push(Stack {
+            number,
+            crates: vec![],
+        });
+    }
+
+    // rows are processed from the bottom, so every crate has to land on top of its stack
+    for (row_index, stacks_row) in stacks_rows.iter().enumerate().rev() {
+        let line = row_index + 1;
+        let mut used_stacks: Vec<usize> = vec![];
+
+        for crate_token in get_crates_row_tokens(stacks_row, line)? {
+            let column = crate_token.start + 1;
+            let matching_stacks: Vec<usize> = number_tokens
+                .iter()
+                .enumerate()
+                .filter(|(_, number_token)| {
+                    number_token.start <= crate_token.end && crate_token.start <= number_token.end
+                })
+                .map(|(stack_index, _)| stack_index)
+                .collect();
+
+            let stack_index = match matching_stacks[..] {
+                [stack_index] => stack_index,
+                _ => return Err(DrawingError::CrateWithoutStack { line, column }),
             };
-        })
-        .collect();
 
-    instructions_data
+            if used_stacks.contains(&stack_index) {
+                return Err(DrawingError::DuplicateCrate { line, column });
+            }
+            if stacks[stack_index].crates.len() != stacks_rows.len() - line {
+                return Err(DrawingError::FloatingCrate { line, column });
+            }
+
+            used_stacks.push(stack_index);
+            stacks[stack_index].crates.push(crate_token.text);
+        }
+    }
+
+    Ok(stacks)
 }
 
-fn get_stacks_data(raw_stacks: &str) -> Vec<Stack> {
-    let mut stacks_rows: Vec<&str> = raw_stacks.split("\n").collect();
-    let stacks_numbers: Vec<u32> = stacks_rows
-        .pop()
-        .unwrap()
-        .split_whitespace()
-        .map(|number| number.parse::<u32>().unwrap())
-        .collect();
-    stacks_rows.reverse();
-    let max_crates_in_stack = stacks_rows.len();
+const HIGHLIGHT_START: &str = "\x1b[1;33m";
+const HIGHLIGHT_END: &str = "\x1b[0m";
+const CLEAR_SCREEN: &str = "\x1b[2J\x1b[H";
 
-    let stacks: Vec<Stack> = stacks_numbers
+// all stacks share one column width so that the numbers row lines up with the crates
+fn get_stack_column_width(stacks: &Vec<Stack>) -> usize {
+    let max_label_width = stacks
         .iter()
-        .map(|stack_number| {
-            let stack_value_index = 1 + ((stack_number - 1) * 4);
+        .flat_map(|stack| stack.crates.iter())
+        .map(|label| label.chars().count() + 2)
+        .max()
+        .unwrap_or(3);
+    let max_number_width = stacks
+        .iter()
+        .map(|stack| stack.number.to_string().len())
+        .max()
+        .unwrap_or(1);
 
-            let mut crates: Vec<char> = Vec::new();
+    return max_label_width.max(max_number_width).max(3);
+}
 
-            for n in 0..max_crates_in_stack {
-                let stack_crate = stacks_rows[n]
-                    .chars()
-                    .nth(stack_value_index as usize)
-                    .unwrap();
+// draws stacks the same way as the puzzle input, crates and numbers are both centred so a crate
+// always overlaps its own number whatever the labels widths, highlighted crates are
+// (stack index, height)
+fn render_stacks(stacks: &Vec<Stack>, highlighted: &Vec<(usize, usize)>) -> String {
+    let width = get_stack_column_width(stacks);
+    let height = stacks
+        .iter()
+        .map(|stack| stack.crates.len())
+        .max()
+        .unwrap_or(0);
+    let mut lines: Vec<String> = vec![];
+
+    for level in (0..height).rev() {
+        let cells: Vec<String> = stacks
+            .iter()
+            .enumerate()
+            .map(|(stack_index, stack)| match stack.crates.get(level) {
+                Some(label) => {
+                    let cell = format!("{:^width$}", format!("[{}]", label), width = width);
 
-                if stack_crate.is_whitespace().not() {
-                    crates.push(stack_crate);
+                    if highlighted.contains(&(stack_index, level)) {
+                        return format!("{}{}{}", HIGHLIGHT_START, cell, HIGHLIGHT_END);
+                    }
+
+                    cell
                 }
-            }
+                None => " ".repeat(width),
+            })
+            .collect();
 
-            crates.reverse();
+        lines.push(cells.join(" "));
+    }
 
-            Stack {
-                number: *stack_number,
-                crates: Cell::new(crates),
-            }
+    let numbers: Vec<String> = stacks
+        .iter()
+        .map(|stack| format!("{:^width$}", stack.number, width = width))
+        .collect();
+    lines.push(numbers.join(" "));
+
+    return lines.join("\n");
+}
+
+// drawing understood by get_stacks_data, so any yard state can be parsed back
+fn serialize_stacks(stacks: &Vec<Stack>) -> String {
+    return render_stacks(stacks, &vec![]);
+}
+
+fn serialize_input(stacks: &Vec<Stack>, instructions: &[Instruction]) -> String {
+    let serialized_instructions: Vec<String> = instructions
+        .iter()
+        .map(|instruction| instruction.to_string())
+        .collect();
+
+    return format!(
+        "{}\n\n{}",
+        serialize_stacks(stacks),
+        serialized_instructions.join("\n")
+    );
+}
+
+struct GeneratorConfig {
+    seed: u64,
+    stacks_count: usize,
+    max_height: usize,
+    alphabet: Vec<char>,
+    instructions_count: usize,
+}
+
+// every stack starts with 1 to max_height crates and moves never take more than the source holds
+fn generate_input(config: &GeneratorConfig) -> (Vec<Stack>, Vec<Instruction>) {
+    let mut random = Random::new(config.seed);
+
+    let mut stacks: Vec<Stack> = (0..config.stacks_count)
+        .map(|index| Stack {
+            number: index as u32 + 1,
+            crates: (0..random.next_in_range(1, config.max_height))
+                .map(|_| config.alphabet[random.next_below(config.alphabet.len())].to_string())
+                .collect(),
         })
         .collect();
 
-    stacks
+    let initial_stacks = stacks.clone();
+    let mut instructions = vec![];
+
+    for _ in 0..config.instructions_count {
+        let non_empty_stacks: Vec<usize> = (0..stacks.len())
+            .filter(|index| stacks[*index].crates.is_empty().not())
+            .collect();
+
+        let src_index = non_empty_stacks[random.next_below(non_empty_stacks.len())];
+        let dst_index = (src_index + random.next_in_range(1, stacks.len() - 1)) % stacks.len();
+        let amount = random.next_in_range(1, stacks[src_index].crates.len());
+
+        let instruction = Instruction {
+            amount: amount as u32,
+            src: src_index as u32 + 1,
+            dst: dst_index as u32 + 1,
+        };
+
+        CrateMover9000.move_crates(&instruction, &mut stacks);
+        instructions.push(instruction);
+    }
+
+    return (initial_stacks, instructions);
 }
 
-fn get_input_data(content: &String) -> Option<(Vec<Instruction>, Vec<Stack>)> {
+fn get_input_data(content: &String) -> Result<(Vec<Instruction>, Vec<Stack>), DrawingError> {
     if let [stacks, instructions] = content.split("\n\n").collect::<Vec<&str>>()[..] {
-        let instructions = get_instructions_data(instructions);
-        let stacks = get_stacks_data(stacks);
+        let instructions = get_instructions_data(instructions)?;
+        let stacks = get_stacks_data(stacks)?;
+
+        return Ok((instructions, stacks));
+    }
+
+    return Err(DrawingError::MissingSections);
+}
+
+trait Crane {
+    fn get_name(&self) -> String;
+
+    // performs one instruction, a crane is free to split it into several lifts
+    fn move_crates(&self, instruction: &Instruction, stacks: &mut Vec<Stack>);
+}
+
+// stacks are numbered from 1 in the drawing, so a stack number is its position plus one
+fn lift_crates(stacks: &mut Vec<Stack>, src: u32, dst: u32, amount: usize, pick_multiple: bool) {
+    let crates_to_move = stacks[src as usize - 1].remove_crates(amount, pick_multiple);
+
+    stacks[dst as usize - 1].add_crates(crates_to_move);
+}
+
+struct CrateMover9000;
+
+impl Crane for CrateMover9000 {
+    fn get_name(&self) -> String {
+        return "CrateMover 9000".to_string();
+    }
+
+    fn move_crates(&self, instruction: &Instruction, stacks: &mut Vec<Stack>) {
+        lift_crates(
+            stacks,
+            instruction.src,
+            instruction.dst,
+            instruction.amount as usize,
+            false,
+        );
+    }
+}
+
+struct CrateMover9001;
+
+impl Crane for CrateMover9001 {
+    fn get_name(&self) -> String {
+        return "CrateMover 9001".to_string();
+    }
+
+    fn move_crates(&self, instruction: &Instruction, stacks: &mut Vec<Stack>) {
+        lift_crates(
+            stacks,
+            instruction.src,
+            instruction.dst,
+            instruction.amount as usize,
+            true,
+        );
+    }
+}
+
+// lifts at most capacity crates at once, keeping order of the lifted ones
+struct LimitedCrateMover {
+    capacity: usize,
+}
+
+impl Crane for LimitedCrateMover {
+    fn get_name(&self) -> String {
+        return format!("CrateMover with capacity {}", self.capacity);
+    }
+
+    fn move_crates(&self, instruction: &Instruction, stacks: &mut Vec<Stack>) {
+        let mut remaining = instruction.amount as usize;
 
-        return Some((instructions, stacks));
+        while remaining > 0 {
+            let lifted = remaining.min(self.capacity);
+
+            lift_crates(stacks, instruction.src, instruction.dst, lifted, true);
+            remaining -= lifted;
+        }
+    }
+}
+
+// lifts only full batches of crates, the rest is moved one by one
+struct BatchCrateMover {
+    batch_size: usize,
+}
+
+impl Crane for BatchCrateMover {
+    fn get_name(&self) -> String {
+        return format!("CrateMover with batches of {}", self.batch_size);
+    }
+
+    fn move_crates(&self, instruction: &Instruction, stacks: &mut Vec<Stack>) {
+        let amount = instruction.amount as usize;
+
+        for _ in 0..amount / self.batch_size {
+            lift_crates(
+                stacks,
+                instruction.src,
+                instruction.dst,
+                self.batch_size,
+                true,
+            );
+        }
+
+        lift_crates(
+            stacks,
+            instruction.src,
+            instruction.dst,
+            amount % self.batch_size,
+            false,
+        );
+    }
+}
+
+// instructions are numbered from 1 in the same order as in the input
+#[derive(Debug)]
+enum SimulationError {
+    UnknownStack {
+        instruction_number: usize,
+        stack: u32,
+        stacks_count: usize,
+    },
+    NotEnoughCrates {
+        instruction_number: usize,
+        stack: u32,
+        height: usize,
+        amount: u32,
+    },
+    IrreversibleInstruction {
+        instruction_number: usize,
+        instruction: String,
+        height: usize,
+    },
+}
+
+impl fmt::Display for SimulationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SimulationError::UnknownStack {
+                instruction_number,
+                stack,
+                stacks_count,
+            } => write!(
+                f,
+                "instruction {} uses stack {} but there are only stacks 1 to {}",
+                instruction_number, stack, stacks_count
+            ),
+            SimulationError::NotEnoughCrates {
+                instruction_number,
+                stack,
+                height,
+                amount,
+            } => write!(
+                f,
+                "instruction {} moves {} crates from stack {} holding only {}",
+                instruction_number, amount, stack, height
+            ),
+            SimulationError::IrreversibleInstruction {
+                instruction_number,
+                instruction,
+                height,
+            } => write!(
+                f,
+                "instruction {} {} can not be undone, its destination holds only {} crates",
+                instruction_number, instruction, height
+            ),
+        }
+    }
+}
+
+fn validate_stacks(
+    instruction: &Instruction,
+    instruction_index: usize,
+    stacks: &Vec<Stack>,
+) -> Result<(), SimulationError> {
+    for stack in [instruction.src, instruction.dst] {
+        if stack == 0 || stack as usize > stacks.len() {
+            return Err(SimulationError::UnknownStack {
+                instruction_number: instruction_index + 1,
+                stack,
+                stacks_count: stacks.len(),
+            });
+        }
     }
 
-    return None;
+    return Ok(());
 }
 
+fn validate_instruction(
+    instruction: &Instruction,
+    instruction_index: usize,
+    stacks: &Vec<Stack>,
+) -> Result<(), SimulationError> {
+    let instruction_number = instruction_index + 1;
+
+    validate_stacks(instruction, instruction_index, stacks)?;
+
+    let height = stacks[instruction.src as usize - 1].crates.len();
+
+    if instruction.amount as usize > height {
+        return Err(SimulationError::NotEnoughCrates {
+            instruction_number,
+            stack: instruction.src,
+            height,
+            amount: instruction.amount,
+        });
+    }
+
+    return Ok(());
+}
+
+fn get_top_crates(stacks: &Vec<Stack>) -> String {
+    return stacks
+        .iter()
+        .map(|stack| stack.get_top_crate().unwrap_or(EMPTY_STACK_PLACEHOLDER))
+        .collect();
+}
+
+// stops at the first invalid instruction, stacks are left as they were before it
 fn rearange_crates(
     instructions: &Vec<Instruction>,
-    stacks: &Vec<Stack>,
-    pick_multiple: bool,
-) -> String {
-    for instruction in instructions {
-        let src_stack = stacks
-            .iter()
-            .find(|stack| stack.number == instruction.src)
-            .unwrap();
-        let dst_stack = stacks
-            .iter()
-            .find(|stack| stack.number == instruction.dst)
-            .unwrap();
+    stacks: &mut Vec<Stack>,
+    crane: &dyn Crane,
+) -> Result<String, SimulationError> {
+    for (index, instruction) in instructions.iter().enumerate() {
+        validate_instruction(instruction, index, stacks)?;
 
-        let crates_to_move = src_stack.remove_crates(instruction.amount as usize, pick_multiple);
-        dst_stack.add_crates(crates_to_move);
+        crane.move_crates(instruction, stacks);
     }
 
-    let result = stacks.iter().fold(String::new(), |acc, cur| {
-        let mut acc_mut = acc.clone();
-        acc_mut.push(cur.get_top_crate());
+    return Ok(get_top_crates(stacks));
+}
 
-        return acc_mut;
-    });
+// crane is "9000", "9001", "limited CAPACITY" or "batch SIZE", CrateMover 9000 when missing
+fn get_crane(options: &[&str]) -> Option<Box<dyn Crane>> {
+    let get_size = |value: &str| value.parse::<usize>().ok().filter(|size| *size > 0);
 
-    return result;
+    match options {
+        [] | ["9000"] => Some(Box::new(CrateMover9000)),
+        ["9001"] => Some(Box::new(CrateMover9001)),
+        ["limited", capacity] => get_size(capacity)
+            .map(|capacity| Box::new(LimitedCrateMover { capacity }) as Box<dyn Crane>),
+        ["batch", batch_size] => get_size(batch_size)
+            .map(|batch_size| Box::new(BatchCrateMover { batch_size }) as Box<dyn Crane>),
+        _ => None,
+    }
+}
+
+// crates taken from the source stack, in their original order, are enough to undo any crane
+struct HistoryEntry {
+    instruction_index: usize,
+    src: u32,
+    dst: u32,
+    removed_crates: Vec<String>,
+}
+
+struct History {
+    entries: Vec<HistoryEntry>,
+}
+
+impl History {
+    fn new() -> History {
+        return History { entries: vec![] };
+    }
+
+    fn apply(
+        &mut self,
+        instruction: &Instruction,
+        instruction_index: usize,
+        stacks: &mut Vec<Stack>,
+        crane: &dyn Crane,
+    ) -> Result<(), SimulationError> {
+        validate_instruction(instruction, instruction_index, stacks)?;
+
+        let src_crates = &stacks[instruction.src as usize - 1].crates;
+        let removed_crates = src_crates[src_crates.len() - instruction.amount as usize..].to_vec();
+
+        crane.move_crates(instruction, stacks);
+
+        self.entries.push(HistoryEntry {
+            instruction_index,
+            src: instruction.src,
+            dst: instruction.dst,
+            removed_crates,
+        });
+
+        return Ok(());
+    }
+
+    // returns index of the undone instruction, None when there is nothing to undo
+    fn undo(&mut self, stacks: &mut Vec<Stack>) -> Option<usize> {
+        let entry = self.entries.pop()?;
+
+        let dst_stack = &mut stacks[entry.dst as usize - 1];
+        dst_stack
+            .crates
+            .truncate(dst_stack.crates.len() - entry.removed_crates.len());
+
+        stacks[entry.src as usize - 1].add_crates(entry.removed_crates);
+
+        return Some(entry.instruction_index);
+    }
+}
+
+// position of every moved crate in the source stack, index 0 being the lowest of them,
+// listed in the order in which the crane leaves them on the destination stack
+fn get_crane_permutation(crane: &dyn Crane, amount: usize) -> Vec<usize> {
+    let mut stacks = vec![
+        Stack {
+            number: 1,
+            crates: (0..amount).map(|index| index.to_string()).collect(),
+        },
+        Stack {
+            number: 2,
+            crates: vec![],
+        },
+    ];
+
+    crane.move_crates(
+        &Instruction {
+            amount: amount as u32,
+            src: 1,
+            dst: 2,
+        },
+        &mut stacks,
+    );
+
+    return stacks[1]
+        .crates
+        .iter()
+        .map(|label| label.parse().unwrap())
+        .collect();
+}
+
+// undoes an instruction without history, moved crates are put back in the order they had
+// before the crane moved them
+fn reverse_instruction(
+    instruction: &Instruction,
+    instruction_index: usize,
+    stacks: &mut Vec<Stack>,
+    crane: &dyn Crane,
+) -> Result<(), SimulationError> {
+    validate_stacks(instruction, instruction_index, stacks)?;
+
+    let dst_stack = &mut stacks[instruction.dst as usize - 1];
+
+    if instruction.amount as usize > dst_stack.crates.len() {
+        return Err(SimulationError::IrreversibleInstruction {
+            instruction_number: instruction_index + 1,
+            instruction: instruction.to_string(),
+            height: dst_stack.crates.len(),
+        });
+    }
+
+    let moved_crates = dst_stack
+        .crates
+        .split_off(dst_stack.crates.len() - instruction.amount as usize);
+
+    let mut original_crates = vec![String::new(); moved_crates.len()];
+
+    for (moved_crate, original_index) in moved_crates
+        .into_iter()
+        .zip(get_crane_permutation(crane, instruction.amount as usize))
+    {
+        original_crates[original_index] = moved_crate;
+    }
+
+    stacks[instruction.src as usize - 1].add_crates(original_crates);
+
+    return Ok(());
+}
+
+fn reconstruct_initial_stacks(
+    instructions: &Vec<Instruction>,
+    final_stacks: &mut Vec<Stack>,
+    crane: &dyn Crane,
+) -> Result<(), SimulationError> {
+    for (index, instruction) in instructions.iter().enumerate().rev() {
+        reverse_instruction(instruction, index, final_stacks, crane)?;
+    }
+
+    return Ok(());
 }
 
 pub fn execute() {
@@ -170,12 +850,395 @@ pub fn execute() {
     let input_data_1 = get_input_data(&content);
     let input_data_2 = get_input_data(&content);
 
-    let (instructions, stacks) = input_data_1.unwrap();
-    let result_1 = rearange_crates(&instructions, &stacks, false);
+    if let Err(error) = &input_data_1 {
+        println!("Invalid input: {}", error);
+
+        return;
+    }
+
+    let (instructions, mut stacks) = input_data_1.unwrap();
+    let result_1 = rearange_crates(&instructions, &mut stacks, &CrateMover9000);
+
+    let (instructions, mut stacks) = input_data_2.unwrap();
+    let result_2 = rearange_crates(&instructions, &mut stacks, &CrateMover9001);
+
+    match (result_1, result_2) {
+        (Ok(result_1), Ok(result_2)) => {
+            println!("Result 1 {}", result_1);
+            println!("Result 2 {}", result_2);
+        }
+        (Err(error), _) | (_, Err(error)) => println!("Invalid instructions: {}", error),
+    }
+}
+
+pub fn execute_with_crane(options: &[&str]) {
+    let crane = match get_crane(options) {
+        Some(crane) => crane,
+        None => {
+            println!("Unknown crane {}", options.join(" "));
+
+            return;
+        }
+    };
+
+    let content = fs::read_to_string(FILE_PATH).expect("Should have been able to read the file");
+
+    match get_input_data(&content) {
+        Ok((instructions, mut stacks)) => {
+            match rearange_crates(&instructions, &mut stacks, crane.as_ref()) {
+                Ok(result) => println!("{} {}", crane.get_name(), result),
+                Err(error) => println!("Invalid instructions: {}", error),
+            }
+        }
+        Err(error) => println!("Invalid input: {}", error),
+    }
+}
+
+// options are "--every N" to draw only every Nth step, "--delay MS" to animate the drawing
+// in place and a crane as accepted by get_crane
+pub fn execute_rendering(options: &[&str]) {
+    let mut every = 1;
+    let mut delay = None;
+    let mut crane_options = vec![];
+    let mut options_iter = options.iter();
+
+    while let Some(option) = options_iter.next() {
+        let value = match *option {
+            "--every" | "--delay" => options_iter.next().and_then(|value| value.parse().ok()),
+            _ => {
+                crane_options.push(*option);
+
+                continue;
+            }
+        };
+
+        match (*option, value) {
+            ("--every", Some(value)) => every = (value as usize).max(1),
+            ("--delay", Some(value)) => delay = Some(value),
+            _ => {
+                println!("Option {} needs a non negative number", option);
 
-    let (instructions, stacks) = input_data_2.unwrap();
-    let result_2 = rearange_crates(&instructions, &stacks, true);
+                return;
+            }
+        }
+    }
+
+    let crane = match get_crane(&crane_options) {
+        Some(crane) => crane,
+        None => {
+            println!("Unknown crane {}", crane_options.join(" "));
+
+            return;
+        }
+    };
+
+    let content = fs::read_to_string(FILE_PATH).expect("Should have been able to read the file");
+
+    let (instructions, mut stacks) = match get_input_data(&content) {
+        Ok(input_data) => input_data,
+        Err(error) => {
+            println!("Invalid input: {}", error);
+
+            return;
+        }
+    };
+
+    let print_frame = |frame: String| {
+        if let Some(delay) = delay {
+            print!("{}", CLEAR_SCREEN);
+            println!("{}", frame);
+            thread::sleep(Duration::from_millis(delay));
+        } else {
+            println!("{}\n", frame);
+        }
+    };
+
+    print_frame(format!("Initial\n{}", render_stacks(&stacks, &vec![])));
+
+    for (index, instruction) in instructions.iter().enumerate() {
+        if let Err(error) = validate_instruction(instruction, index, &stacks) {
+            println!("Invalid instructions: {}", error);
+
+            return;
+        }
+
+        crane.move_crates(instruction, &mut stacks);
+
+        let step = index + 1;
+
+        if step % every != 0 && step != instructions.len() {
+            continue;
+        }
 
-    println!("Result 1 {}", result_1);
-    println!("Result 2 {}", result_2);
+        let dst_index = instruction.dst as usize - 1;
+        let dst_height = stacks[dst_index].crates.len();
+        let highlighted = (dst_height - instruction.amount as usize..dst_height)
+            .map(|level| (dst_index, level))
+            .collect();
+
+        print_frame(format!(
+            "Step {} move {} from {} to {}\n{}",
+            step,
+            instruction.amount,
+            instruction.src,
+            instruction.dst,
+            render_stacks(&stacks, &highlighted)
+        ));
+    }
+}
+
+// prints the yard after given amount of steps followed by the remaining instructions,
+// crane is chosen as in get_crane
+pub fn execute_saving(step: &str, crane_options: &[&str]) {
+    let step = match step.parse::<usize>() {
+        Ok(step) => step,
+        Err(_) => {
+            println!("Step {} has to be a non negative number", step);
+
+            return;
+        }
+    };
+
+    let crane = match get_crane(crane_options) {
+        Some(crane) => crane,
+        None => {
+            println!("Unknown crane {}", crane_options.join(" "));
+
+            return;
+        }
+    };
+
+    let content = fs::read_to_string(FILE_PATH).expect("Should have been able to read the file");
+
+    let (instructions, mut stacks) = match get_input_data(&content) {
+        Ok(input_data) => input_data,
+        Err(error) => {
+            println!("Invalid input: {}", error);
+
+            return;
+        }
+    };
+
+    let step = step.min(instructions.len());
+
+    for (index, instruction) in instructions[..step].iter().enumerate() {
+        if let Err(error) = validate_instruction(instruction, index, &stacks) {
+            println!("Invalid instructions: {}", error);
+
+            return;
+        }
+
+        crane.move_crates(instruction, &mut stacks);
+    }
+
+    println!("{}", serialize_input(&stacks, &instructions[step..]));
+}
+
+// prints a random input, the same seed always gives the same input
+pub fn execute_generator(
+    seed: &str,
+    stacks_count: &str,
+    max_height: &str,
+    instructions_count: &str,
+    alphabet: Option<&str>,
+) {
+    let usage = "Generator needs a numeric seed, at least 2 stacks, positive height, a count of instructions and alphabet without ] or whitespace";
+
+    let numbers = (
+        seed.parse::<u64>(),
+        stacks_count.parse::<usize>(),
+        max_height.parse::<usize>(),
+        instructions_count.parse::<usize>(),
+    );
+
+    let config = match numbers {
+        (Ok(seed), Ok(stacks_count), Ok(max_height), Ok(instructions_count)) => GeneratorConfig {
+            seed,
+            stacks_count,
+            max_height,
+            alphabet: alphabet
+                .unwrap_or("ABCDEFGHIJKLMNOPQRSTUVWXYZ")
+                .chars()
+                .collect(),
+            instructions_count,
+        },
+        _ => {
+            println!("{}", usage);
+
+            return;
+        }
+    };
+
+    let is_alphabet_valid = config.alphabet.is_empty().not()
+        && config
+            .alphabet
+            .iter()
+            .all(|label| *label != ']' && label.is_whitespace().not());
+
+    if config.stacks_count < 2 || config.max_height == 0 || is_alphabet_valid.not() {
+        println!("{}", usage);
+
+        return;
+    }
+
+    let (initial_stacks, instructions) = generate_input(&config);
+
+    println!("{}", serialize_input(&initial_stacks, &instructions));
+}
+
+// runs all instructions and then walks the history back to show the yard after given step
+pub fn execute_step(step: &str, crane_options: &[&str]) {
+    let step = match step.parse::<usize>() {
+        Ok(step) => step,
+        Err(_) => {
+            println!("Step {} has to be a non negative number", step);
+
+            return;
+        }
+    };
+
+    let crane = match get_crane(crane_options) {
+        Some(crane) => crane,
+        None => {
+            println!("Unknown crane {}", crane_options.join(" "));
+
+            return;
+        }
+    };
+
+    let content = fs::read_to_string(FILE_PATH).expect("Should have been able to read the file");
+
+    let (instructions, mut stacks) = match get_input_data(&content) {
+        Ok(input_data) => input_data,
+        Err(error) => {
+            println!("Invalid input: {}", error);
+
+            return;
+        }
+    };
+
+    let mut history = History::new();
+
+    for (index, instruction) in instructions.iter().enumerate() {
+        if let Err(error) = history.apply(instruction, index, &mut stacks, crane.as_ref()) {
+            println!("Invalid instructions: {}", error);
+
+            return;
+        }
+    }
+
+    while history.entries.len() > step {
+        history.undo(&mut stacks);
+    }
+
+    println!(
+        "Step {}\n{}",
+        history.entries.len(),
+        render_stacks(&stacks, &vec![])
+    );
+}
+
+// file holds the final drawing and the instructions which led to it
+pub fn execute_reconstruction(path: &str, crane_options: &[&str]) {
+    let crane = match get_crane(crane_options) {
+        Some(crane) => crane,
+        None => {
+            println!("Unknown crane {}", crane_options.join(" "));
+
+            return;
+        }
+    };
+
+    let content = fs::read_to_string(path).expect("Should have been able to read the file");
+
+    let (instructions, mut stacks) = match get_input_data(&content) {
+        Ok(input_data) => input_data,
+        Err(error) => {
+            println!("Invalid input: {}", error);
+
+            return;
+        }
+    };
+
+    match reconstruct_initial_stacks(&instructions, &mut stacks, crane.as_ref()) {
+        Ok(()) => println!("{}", serialize_input(&stacks, &instructions)),
+        Err(error) => println!("Invalid instructions: {}", error),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_crates(stacks: &Vec<Stack>) -> Vec<(u32, Vec<String>)> {
+        return stacks
+            .iter()
+            .map(|stack| (stack.number, stack.crates.clone()))
+            .collect();
+    }
+
+    fn check_round_trip(stacks: &Vec<Stack>) {
+        let serialized = serialize_stacks(stacks);
+        let parsed = get_stacks_data(&serialized).unwrap();
+
+        assert_eq!(get_crates(&parsed), get_crates(stacks));
+        assert_eq!(serialize_stacks(&parsed), serialized);
+    }
+
+    #[test]
+    fn round_trips_mixed_label_widths() {
+        let labels: Vec<Vec<&str>> = vec![
+            vec!["ABCDE", "A"],
+            vec!["A"],
+            vec![],
+            vec!["LONGER LABEL", "B", "CC"],
+            vec!["Z", "YYYYYYY"],
+        ];
+
+        check_round_trip(
+            &labels
+                .iter()
+                .enumerate()
+                .map(|(index, crates)| Stack {
+                    number: index as u32 + 1,
+                    crates: crates.iter().map(|label| label.to_string()).collect(),
+                })
+                .collect(),
+        );
+    }
+
+    #[test]
+    fn round_trips_random_yards() {
+        let mut random = Random::new(42);
+
+        for _ in 0..200 {
+            let stacks: Vec<Stack> = (0..random.next_in_range(1, 12))
+                .map(|index| Stack {
+                    number: index as u32 + 1,
+                    crates: (0..random.next_below(5))
+                        .map(|_| {
+                            (0..random.next_in_range(1, 8))
+                                .map(|_| (b'A' + random.next_below(26) as u8) as char)
+                                .collect()
+                        })
+                        .collect(),
+                })
+                .collect();
+
+            check_round_trip(&stacks);
+        }
+    }
+
+    #[test]
+    fn round_trips_puzzle_input() {
+        let content = fs::read_to_string(FILE_PATH).unwrap();
+        let raw_stacks = content.split("\n\n").next().unwrap();
+        let stacks = get_stacks_data(raw_stacks).unwrap();
+
+        assert!(serialize_stacks(&stacks)
+            .lines()
+            .zip(raw_stacks.lines())
+            .all(|(serialized, raw)| serialized.trim_end() == raw.trim_end()));
+        check_round_trip(&stacks);
+    }
 }
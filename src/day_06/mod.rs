@@ -1,76 +1,653 @@
 #![allow(dead_code)]
 #![allow(unused_variables)]
-use std::{collections::HashSet, fs, str::Chars};
+use std::{
+    collections::HashMap,
+    fmt,
+    fs::File,
+    hash::Hash,
+    io::{self, BufReader, Bytes, Read},
+};
 
 const FILE_PATH: &str = "src/day_06/resources/input.txt";
 const PACKET_UNIQUE_CHARS_COUNT: usize = 4;
 const MESSAGE_UNIQUE_CHARS_COUNT: usize = 14;
+const DEFAULT_SEPARATORS: &str = "0a,0d";
 
-fn check_is_valid_sequence(
-    already_read_items: &mut Vec<char>,
-    sequence_start_offset: &mut i32,
-    sequence_length: usize,
-) -> bool {
-    let sequence_to_check = already_read_items
-        [(*sequence_start_offset as usize)..(*sequence_start_offset as usize) + sequence_length]
-        .to_vec();
-    let sequence_set: HashSet<char> = HashSet::from_iter(sequence_to_check);
+// decodes UTF-8 chars from a reader without loading the whole input
+struct CharReader<R: Read> {
+    bytes: Bytes<BufReader<R>>,
+}
+
+impl<R: Read> CharReader<R> {
+    fn new(reader: R) -> CharReader<R> {
+        return CharReader {
+            bytes: BufReader::new(reader).bytes(),
+        };
+    }
+}
+
+impl<R: Read> Iterator for CharReader<R> {
+    type Item = io::Result<char>;
+
+    fn next(&mut self) -> Option<io::Result<char>> {
+        let first_byte = match self.bytes.next()? {
+            Ok(byte) => byte,
+            Err(error) => return Some(Err(error)),
+        };
+
+        // ASCII is the common case and needs no decoding
+        let char_length = match first_byte.leading_ones() {
+            0 => return Some(Ok(first_byte as char)),
+            length @ 2..=4 => length as usize,
+            _ => return Some(Err(io::Error::from(io::ErrorKind::InvalidData))),
+        };
+
+        let mut encoded = [first_byte, 0, 0, 0];
+
+        for encoded_byte in &mut encoded[1..char_length] {
+            match self.bytes.next() {
+                Some(Ok(byte)) => *encoded_byte = byte,
+                Some(Err(error)) => return Some(Err(error)),
+                None => return Some(Err(io::Error::from(io::ErrorKind::UnexpectedEof))),
+            }
+        }
+
+        return Some(
+            std::str::from_utf8(&encoded[..char_length])
+                .map(|decoded| decoded.chars().next().unwrap())
+                .map_err(|_| io::Error::from(io::ErrorKind::InvalidData)),
+        );
+    }
+}
+
+// sliding window over the last items, remembering where every item was seen last, so the
+// window only has to move its start past the previous occurrence of each new item
+struct MarkerDetector<T> {
+    length: usize,
+    last_seen: HashMap<T, usize>,
+    window_start: usize,
+    position: usize,
+}
+
+impl<T: Eq + Hash> MarkerDetector<T> {
+    fn new(length: usize) -> MarkerDetector<T> {
+        return MarkerDetector {
+            length,
+            last_seen: HashMap::new(),
+            window_start: 1,
+            position: 0,
+        };
+    }
+
+    // returns amount of items read so far when the last `length` of them are all different
+    fn push(&mut self, item: T) -> Option<usize> {
+        self.position += 1;
+
+        if let Some(last_position) = self.last_seen.insert(item, self.position) {
+            self.window_start = self.window_start.max(last_position + 1);
+        }
+
+        if self.position + 1 - self.window_start >= self.length {
+            return Some(self.position);
+        }
+
+        return None;
+    }
+}
+
+// None when the datastream ends before any marker
+fn detect_sequence_of_size(length: usize, reader: impl Read) -> io::Result<Option<usize>> {
+    let mut detector = MarkerDetector::new(length);
+
+    for item in CharReader::new(reader) {
+        if let Some(position) = detector.push(item?) {
+            return Ok(Some(position));
+        }
+    }
+
+    return Ok(None);
+}
+
+// every position where the last `length` chars are all different, markers may overlap
+fn detect_all_sequences_of_size(length: usize, reader: impl Read) -> io::Result<Vec<usize>> {
+    let mut detector = MarkerDetector::new(length);
+    let mut positions = vec![];
+
+    for item in CharReader::new(reader) {
+        if let Some(position) = detector.push(item?) {
+            positions.push(position);
+        }
+    }
+
+    return Ok(positions);
+}
+
+// positions count chars from the beginning of the whole datastream
+struct Segment {
+    marker_position: usize,
+    marker: String,
+    payload: String,
+}
+
+struct Segmentation {
+    preamble: String,
+    segments: Vec<Segment>,
+}
+
+// splits the datastream at non overlapping markers, each segment holds its marker and the data
+// up to the next marker, data before the first marker is kept as preamble
+fn segment_datastream(length: usize, reader: impl Read, offset: usize) -> io::Result<Segmentation> {
+    let mut detector = MarkerDetector::new(length);
+    let mut segmentation = Segmentation {
+        preamble: String::new(),
+        segments: vec![],
+    };
+    let mut buffer: Vec<char> = vec![];
+
+    for (position, item) in (offset + 1..).zip(CharReader::new(reader)) {
+        buffer.push(item?);
+
+        if detector.push(buffer[buffer.len() - 1]).is_some() {
+            let marker: String = buffer
+                .split_off(buffer.len() - length)
+                .into_iter()
+                .collect();
+            let payload: String = buffer.drain(..).collect();
+
+            match segmentation.segments.last_mut() {
+                Some(segment) => segment.payload = payload,
+                None => segmentation.preamble = payload,
+            }
 
-    return sequence_set.len() == sequence_length;
+            segmentation.segments.push(Segment {
+                marker_position: position,
+                marker,
+                payload: String::new(),
+            });
+            detector = MarkerDetector::new(length);
+        }
+    }
+
+    let rest: String = buffer.into_iter().collect();
+
+    match segmentation.segments.last_mut() {
+        Some(segment) => segment.payload = rest,
+        None => segmentation.preamble = rest,
+    }
+
+    return Ok(segmentation);
 }
 
-fn check_next_buffer_item(
-    already_read_items: &mut Vec<char>,
-    sequence_start_offset: &mut i32,
-    current_char: char,
-    remaining_buffer: &mut Chars,
-    sequence_length: usize,
-) -> usize {
-    already_read_items.push(current_char);
+#[derive(Clone, Copy)]
+enum Alphabet {
+    Lowercase,
+    Ascii,
+    Bytes,
+}
 
-    if already_read_items.len() >= sequence_length {
-        let is_valid =
-            check_is_valid_sequence(already_read_items, sequence_start_offset, sequence_length);
+impl Alphabet {
+    fn from_name(name: &str) -> Option<Alphabet> {
+        match name {
+            "lowercase" => Some(Alphabet::Lowercase),
+            "ascii" => Some(Alphabet::Ascii),
+            "bytes" => Some(Alphabet::Bytes),
+            _ => None,
+        }
+    }
 
-        if is_valid {
-            return already_read_items.len();
+    fn contains(&self, byte: u8) -> bool {
+        match self {
+            Alphabet::Lowercase => byte.is_ascii_lowercase(),
+            Alphabet::Ascii => byte.is_ascii(),
+            Alphabet::Bytes => true,
         }
+    }
+}
 
-        *sequence_start_offset += 1;
+#[derive(Debug)]
+enum ScanError {
+    Io(io::Error),
+    ForeignByte { byte: u8, offset: usize },
+}
+
+impl fmt::Display for ScanError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ScanError::Io(error) => write!(f, "can not read datastream: {}", error),
+            ScanError::ForeignByte { byte, offset } => write!(
+                f,
+                "byte {:#04x} at offset {} is not part of the alphabet",
+                byte, offset
+            ),
+        }
+    }
+}
+
+impl From<io::Error> for ScanError {
+    fn from(error: io::Error) -> ScanError {
+        return ScanError::Io(error);
     }
+}
+
+// same sliding window as MarkerDetector, but over raw bytes with tables indexed by byte value,
+// separators are skipped without breaking the window
+struct ByteMarkerDetector {
+    length: usize,
+    alphabet: Alphabet,
+    separators: [bool; 256],
+    last_seen: [usize; 256],
+    window_start: usize,
+    position: usize,
+    offset: usize,
+}
+
+impl ByteMarkerDetector {
+    fn new(length: usize, alphabet: Alphabet, separators: &[u8]) -> ByteMarkerDetector {
+        let mut separators_table = [false; 256];
+
+        for separator in separators {
+            separators_table[*separator as usize] = true;
+        }
+
+        return ByteMarkerDetector {
+            length,
+            alphabet,
+            separators: separators_table,
+            last_seen: [0; 256],
+            window_start: 1,
+            position: 0,
+            offset: 0,
+        };
+    }
+
+    // forgets the window but keeps counting offsets
+    fn restart(&mut self) {
+        self.last_seen = [0; 256];
+        self.window_start = self.position + 1;
+    }
+
+    // returns amount of bytes read so far, separators included, when the last `length` data
+    // bytes are all different
+    fn push(&mut self, byte: u8) -> Result<Option<usize>, ScanError> {
+        self.offset += 1;
+
+        if self.separators[byte as usize] {
+            return Ok(None);
+        }
+        if !self.alphabet.contains(byte) {
+            return Err(ScanError::ForeignByte {
+                byte,
+                offset: self.offset,
+            });
+        }
+
+        self.position += 1;
+
+        let last_position = self.last_seen[byte as usize];
+
+        if last_position > 0 {
+            self.window_start = self.window_start.max(last_position + 1);
+        }
+
+        self.last_seen[byte as usize] = self.position;
+
+        if self.position + 1 - self.window_start >= self.length {
+            return Ok(Some(self.offset));
+        }
+
+        return Ok(None);
+    }
+}
+
+fn detect_bytes_sequence(
+    mut detector: ByteMarkerDetector,
+    reader: impl Read,
+) -> Result<Option<usize>, ScanError> {
+    for byte in BufReader::new(reader).bytes() {
+        if let Some(offset) = detector.push(byte?)? {
+            return Ok(Some(offset));
+        }
+    }
+
+    return Ok(None);
+}
 
-    check_next_buffer_item(
-        already_read_items,
-        sequence_start_offset,
-        remaining_buffer.next().unwrap(),
-        remaining_buffer,
-        sequence_length,
-    )
+enum FollowEvent {
+    Marker(usize),
+    SkippedByte { byte: u8, offset: usize },
 }
 
-fn detect_sequence_of_size(length: usize, content: &String) -> usize {
-    let mut buffer = content.chars();
+// reports every marker while reading, the window restarts after each marker so consecutive
+// markers do not overlap, the reader is consumed in whatever chunks it delivers, bytes outside
+// the alphabet are reported and skipped as a live signal can not be read again
+fn follow_markers(
+    mut detector: ByteMarkerDetector,
+    reader: impl Read,
+    mut on_event: impl FnMut(FollowEvent),
+) -> io::Result<()> {
+    for byte in BufReader::new(reader).bytes() {
+        match detector.push(byte?) {
+            Ok(Some(offset)) => {
+                on_event(FollowEvent::Marker(offset));
+                detector.restart();
+            }
+            Ok(None) => {}
+            Err(ScanError::ForeignByte { byte, offset }) => {
+                on_event(FollowEvent::SkippedByte { byte, offset })
+            }
+            Err(ScanError::Io(error)) => return Err(error),
+        }
+    }
 
-    let mut already_read_items: Vec<char> = Vec::new();
-    let mut sequence_start_offset = 0;
+    return Ok(());
+}
 
-    let result = check_next_buffer_item(
-        &mut already_read_items,
-        &mut sequence_start_offset,
-        buffer.next().unwrap(),
-        &mut buffer,
-        length,
-    );
+// separators are given as comma separated hex values like "0a,0d"
+fn parse_separators(separators: &str) -> Option<Vec<u8>> {
+    return separators
+        .split(",")
+        .filter(|separator| !separator.is_empty())
+        .map(|separator| u8::from_str_radix(separator, 16).ok())
+        .collect();
+}
 
-    return result;
+fn print_marker_result(result_number: u32, length: usize, marker: Option<usize>) {
+    match marker {
+        Some(position) => println!("Result {} {}", result_number, position),
+        None => println!(
+            "Result {} no marker of {} different chars found",
+            result_number, length
+        ),
+    }
 }
 
 pub fn execute() {
-    let content = fs::read_to_string(FILE_PATH).expect("Should have been able to read the file");
+    let open_file = || File::open(FILE_PATH).expect("Should have been able to read the file");
+
+    let result_1 = detect_sequence_of_size(PACKET_UNIQUE_CHARS_COUNT, open_file())
+        .expect("Should have been able to read the datastream");
+    let result_2 = detect_sequence_of_size(MESSAGE_UNIQUE_CHARS_COUNT, open_file())
+        .expect("Should have been able to read the datastream");
+
+    print_marker_result(1, PACKET_UNIQUE_CHARS_COUNT, result_1);
+    print_marker_result(2, MESSAGE_UNIQUE_CHARS_COUNT, result_2);
+}
+
+// a marker needs at least one char, a zero length would match at every position
+fn parse_length(length: &str) -> Option<usize> {
+    return length.parse::<usize>().ok().filter(|length| *length > 0);
+}
+
+pub fn execute_all_markers(length: &str) {
+    let length = match parse_length(length) {
+        Some(length) => length,
+        None => {
+            println!("Length {} has to be a positive number", length);
+
+            return;
+        }
+    };
+
+    let file = File::open(FILE_PATH).expect("Should have been able to read the file");
+
+    let positions = detect_all_sequences_of_size(length, file)
+        .expect("Should have been able to read the datastream");
+
+    for position in &positions {
+        println!("{}", position);
+    }
+
+    println!("Markers {}", positions.len());
+}
+
+// messages are split at start-of-message markers and their payloads at start-of-packet markers
+pub fn execute_segmentation() {
+    let file = File::open(FILE_PATH).expect("Should have been able to read the file");
+
+    let messages = segment_datastream(MESSAGE_UNIQUE_CHARS_COUNT, file, 0)
+        .expect("Should have been able to read the datastream");
+
+    println!("Preamble {}", messages.preamble);
+
+    for message in &messages.segments {
+        println!("Message {} at {}", message.marker, message.marker_position);
+
+        let packets = segment_datastream(
+            PACKET_UNIQUE_CHARS_COUNT,
+            message.payload.as_bytes(),
+            message.marker_position,
+        )
+        .unwrap();
+
+        println!("  Preamble {}", packets.preamble);
+
+        for packet in &packets.segments {
+            println!(
+                "  Packet {} at {} {}",
+                packet.marker, packet.marker_position, packet.payload
+            );
+        }
+    }
+}
+
+fn get_option<'a>(options: &[&'a str], name: &str) -> Option<&'a str> {
+    return options
+        .iter()
+        .position(|option| *option == name)
+        .and_then(|index| options.get(index + 1))
+        .copied();
+}
+
+// options are "--alphabet lowercase|ascii|bytes" and "--separators HEX,HEX"
+fn get_byte_detector(
+    length: usize,
+    options: &[&str],
+    default_alphabet: &str,
+) -> Result<ByteMarkerDetector, String> {
+    let alphabet_name = get_option(options, "--alphabet").unwrap_or(default_alphabet);
+    let alphabet =
+        Alphabet::from_name(alphabet_name).ok_or(format!("Unknown alphabet {}", alphabet_name))?;
+
+    let separators_list = get_option(options, "--separators").unwrap_or(DEFAULT_SEPARATORS);
+    let separators = parse_separators(separators_list)
+        .ok_or(format!("Invalid separators {}", separators_list))?;
+
+    return Ok(ByteMarkerDetector::new(length, alphabet, &separators));
+}
+
+// detector options and "--file PATH"
+pub fn execute_bytes_scan(length: &str, options: &[&str]) {
+    let length = match parse_length(length) {
+        Some(length) => length,
+        None => {
+            println!("Length {} has to be a positive number", length);
+
+            return;
+        }
+    };
+
+    let detector = match get_byte_detector(length, options, "lowercase") {
+        Ok(detector) => detector,
+        Err(message) => {
+            println!("{}", message);
+
+            return;
+        }
+    };
+
+    let path = get_option(options, "--file").unwrap_or(FILE_PATH);
+    let file = File::open(path).expect("Should have been able to read the file");
+
+    match detect_bytes_sequence(detector, file) {
+        Ok(Some(offset)) => println!("Result {}", offset),
+        Ok(None) => println!("No marker of {} different bytes found", length),
+        Err(error) => println!("Invalid datastream: {}", error),
+    }
+}
+
+// detector options and "--length N", markers are printed as soon as they arrive on stdin,
+// any byte is data unless another alphabet is given
+pub fn execute_follow(options: &[&str]) {
+    let length =
+        get_option(options, "--length").map_or(Some(PACKET_UNIQUE_CHARS_COUNT), parse_length);
+
+    let length = match length {
+        Some(length) => length,
+        None => {
+            println!("Option --length needs a positive number");
+
+            return;
+        }
+    };
+
+    let detector = match get_byte_detector(length, options, "bytes") {
+        Ok(detector) => detector,
+        Err(message) => {
+            println!("{}", message);
 
-    let result_1 = detect_sequence_of_size(PACKET_UNIQUE_CHARS_COUNT, &content);
-    let result_2 = detect_sequence_of_size(MESSAGE_UNIQUE_CHARS_COUNT, &content);
+            return;
+        }
+    };
+
+    let result = follow_markers(detector, io::stdin().lock(), |event| match event {
+        FollowEvent::Marker(offset) => println!("Marker at {}", offset),
+        FollowEvent::SkippedByte { byte, offset } => {
+            println!("Skipped byte {:#04x} at offset {}", byte, offset)
+        }
+    });
+
+    if let Err(error) = result {
+        println!("Invalid datastream: {}", error);
+    }
+}
 
-    println!("Result 1 {}", result_1);
-    println!("Result 2 {}", result_2);
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detect_in_test_file(file_number: usize, length: usize) -> Option<usize> {
+        let path = format!("src/day_06/resources/input_test{}.txt", file_number);
+
+        return detect_sequence_of_size(length, File::open(path).unwrap()).unwrap();
+    }
+
+    #[test]
+    fn detects_packet_markers_in_test_files() {
+        let expected = [7, 5, 6, 10, 11];
+
+        for (file_number, position) in expected.iter().enumerate() {
+            assert_eq!(
+                detect_in_test_file(file_number, PACKET_UNIQUE_CHARS_COUNT),
+                Some(*position)
+            );
+        }
+    }
+
+    #[test]
+    fn detects_message_markers_in_test_files() {
+        let expected = [19, 23, 23, 29, 26];
+
+        for (file_number, position) in expected.iter().enumerate() {
+            assert_eq!(
+                detect_in_test_file(file_number, MESSAGE_UNIQUE_CHARS_COUNT),
+                Some(*position)
+            );
+        }
+    }
+
+    #[test]
+    fn reports_missing_marker() {
+        assert_eq!(
+            detect_sequence_of_size(4, "abcabcabca".as_bytes()).unwrap(),
+            None
+        );
+        assert_eq!(detect_sequence_of_size(4, "abc".as_bytes()).unwrap(), None);
+        assert_eq!(detect_sequence_of_size(4, "".as_bytes()).unwrap(), None);
+        assert_eq!(
+            detect_sequence_of_size(14, "abcdefghijklm".as_bytes()).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn decodes_chars_of_any_length() {
+        let decoded: io::Result<String> = CharReader::new("aé€𝄞b".as_bytes()).collect();
+
+        assert_eq!(decoded.unwrap(), "aé€𝄞b");
+        assert!(CharReader::new(&[b'a', 0xff][..]).nth(1).unwrap().is_err());
+        assert!(CharReader::new(&[0xe2, 0x82][..]).next().unwrap().is_err());
+    }
+
+    #[test]
+    fn detects_all_markers() {
+        assert_eq!(
+            detect_all_sequences_of_size(4, "abcdeaabcd".as_bytes()).unwrap(),
+            vec![4, 5, 6, 10]
+        );
+    }
+
+    #[test]
+    fn segments_datastream_at_markers() {
+        let segmentation = segment_datastream(3, "aaabcbbbdefq".as_bytes(), 0).unwrap();
+
+        assert_eq!(segmentation.preamble, "aa");
+        assert_eq!(segmentation.segments.len(), 2);
+        assert_eq!(segmentation.segments[0].marker, "abc");
+        assert_eq!(segmentation.segments[0].marker_position, 5);
+        assert_eq!(segmentation.segments[0].payload, "bb");
+        assert_eq!(segmentation.segments[1].marker, "bde");
+        assert_eq!(segmentation.segments[1].marker_position, 10);
+        assert_eq!(segmentation.segments[1].payload, "fq");
+    }
+
+    #[test]
+    fn detects_bytes_marker_across_separators() {
+        let detector = ByteMarkerDetector::new(4, Alphabet::Lowercase, b"\n");
+
+        assert_eq!(
+            detect_bytes_sequence(detector, "aab\nc\nd".as_bytes()).unwrap(),
+            Some(7)
+        );
+    }
+
+    #[test]
+    fn rejects_bytes_outside_alphabet() {
+        let detector = ByteMarkerDetector::new(4, Alphabet::Ascii, &[]);
+
+        assert!(matches!(
+            detect_bytes_sequence(detector, &[b'a', 0xff, b'b'][..]),
+            Err(ScanError::ForeignByte {
+                byte: 0xff,
+                offset: 2
+            })
+        ));
+    }
+
+    #[test]
+    fn follows_consecutive_markers() {
+        let detector = ByteMarkerDetector::new(3, Alphabet::Lowercase, b"\n");
+        let mut offsets = vec![];
+        let mut skipped = vec![];
+
+        follow_markers(
+            detector,
+            "abcab\nc a\nbcx".as_bytes(),
+            |event| match event {
+                FollowEvent::Marker(offset) => offsets.push(offset),
+                FollowEvent::SkippedByte { byte, offset } => skipped.push((byte, offset)),
+            },
+        )
+        .unwrap();
+
+        assert_eq!(offsets, vec![3, 7, 12]);
+        assert_eq!(skipped, vec![(b' ', 8)]);
+    }
+
+    #[test]
+    fn detects_marker_at_end_of_stream() {
+        assert_eq!(
+            detect_sequence_of_size(4, "aaabcd".as_bytes()).unwrap(),
+            Some(6)
+        );
+    }
 }
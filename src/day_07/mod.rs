@@ -1,8 +1,6 @@
 #![allow(dead_code)]
 #![allow(unused_variables)]
-use lazy_static::lazy_static;
-use regex::Regex;
-use std::{cell::RefCell, fs, rc::Rc};
+use std::{cell::RefCell, fmt, fs, rc::Rc};
 
 const FILE_PATH: &str = "src/day_07/resources/input.txt";
 
@@ -10,17 +8,114 @@ const DIRECTORY_SIZE_THRESHOLD: u32 = 100000;
 const TOTAL_DISK_SPACE: u32 = 70000000;
 const REQUIRED_DISK_SPACE: u32 = 30000000;
 
-lazy_static! {
-    static ref CD_PARENT_PATTERN: Regex = Regex::new(r"cd \.\.").unwrap();
-    static ref CD_CHILD_PATTERN: Regex = Regex::new(r"cd [a-zA-Z]+").unwrap();
-    static ref LS_PATTERN: Regex = Regex::new(r"ls").unwrap();
-    static ref DIRECTORY_CHILD: Regex = Regex::new(r"dir [a-zA-Z]+").unwrap();
-    static ref FILE_CHILD: Regex = Regex::new(r"[0-9]+ *").unwrap();
+enum Destination {
+    Root,
+    Parent,
+    Child(String),
 }
 
-struct Command {
-    command: String,
-    output: Vec<String>,
+// one line of the transcript, names are everything after the first separating space
+enum TranscriptLine {
+    ChangeDirectory(Destination),
+    List,
+    Directory(String),
+    File { size: u32, name: String },
+}
+
+#[derive(Debug)]
+enum TranscriptError {
+    UnknownCommand { line_number: usize, command: String },
+    UnexpectedOutput { line_number: usize, line: String },
+    MalformedOutput { line_number: usize, line: String },
+    NotADirectory { line_number: usize, name: String },
+}
+
+impl fmt::Display for TranscriptError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TranscriptError::UnknownCommand {
+                line_number,
+                command,
+            } => write!(f, "unknown command {} at line {}", command, line_number),
+            TranscriptError::UnexpectedOutput { line_number, line } => write!(
+                f,
+                "output {} at line {} does not follow ls",
+                line, line_number
+            ),
+            TranscriptError::MalformedOutput { line_number, line } => {
+                write!(f, "malformed ls output {} at line {}", line, line_number)
+            }
+            TranscriptError::NotADirectory { line_number, name } => {
+                write!(f, "cd into file {} at line {}", name, line_number)
+            }
+        }
+    }
+}
+
+fn parse_command(command: &str, line_number: usize) -> Result<TranscriptLine, TranscriptError> {
+    match command.split_once(" ") {
+        Some(("cd", "/")) => Ok(TranscriptLine::ChangeDirectory(Destination::Root)),
+        Some(("cd", "..")) => Ok(TranscriptLine::ChangeDirectory(Destination::Parent)),
+        Some(("cd", name)) if !name.is_empty() => Ok(TranscriptLine::ChangeDirectory(
+            Destination::Child(name.to_string()),
+        )),
+        None if command == "ls" => Ok(TranscriptLine::List),
+        _ => Err(TranscriptError::UnknownCommand {
+            line_number,
+            command: command.to_string(),
+        }),
+    }
+}
+
+fn parse_output(line: &str, line_number: usize) -> Result<TranscriptLine, TranscriptError> {
+    let entry = match line.split_once(" ") {
+        Some(("dir", name)) if !name.is_empty() => {
+            Some(TranscriptLine::Directory(name.to_string()))
+        }
+        Some((size, name)) if !name.is_empty() => {
+            size.parse::<u32>().ok().map(|size| TranscriptLine::File {
+                size,
+                name: name.to_string(),
+            })
+        }
+        _ => None,
+    };
+
+    return entry.ok_or(TranscriptError::MalformedOutput {
+        line_number,
+        line: line.to_string(),
+    });
+}
+
+// output lines are only accepted right after ls or other output lines, parsed lines keep their
+// line numbers
+fn parse_transcript(content: &str) -> Result<Vec<(usize, TranscriptLine)>, TranscriptError> {
+    let mut transcript = vec![];
+    let mut is_listing = false;
+
+    for (index, line) in content.lines().enumerate() {
+        let line_number = index + 1;
+
+        if line.is_empty() {
+            continue;
+        }
+
+        let parsed_line = match line.strip_prefix("$ ") {
+            Some(command) => parse_command(command, line_number)?,
+            None if is_listing => parse_output(line, line_number)?,
+            None => {
+                return Err(TranscriptError::UnexpectedOutput {
+                    line_number,
+                    line: line.to_string(),
+                })
+            }
+        };
+
+        is_listing = !matches!(parsed_line, TranscriptLine::ChangeDirectory(_));
+        transcript.push((line_number, parsed_line));
+    }
+
+    return Ok(transcript);
 }
 
 struct Node {
@@ -44,14 +139,8 @@ impl Node {
         self.children.push(child_node);
     }
 
-    fn get_child_by_name(&self, name: String) -> &Rc<RefCell<Node>> {
-        let child = self
-            .children
-            .iter()
-            .find(|node| node.borrow().name == name)
-            .unwrap();
-
-        return child;
+    fn get_child_by_name(&self, name: &str) -> Option<&Rc<RefCell<Node>>> {
+        return self.children.iter().find(|node| node.borrow().name == name);
     }
 
     fn get_total_size(&self) -> u32 {
@@ -77,8 +166,7 @@ impl Node {
 
         let mut subsubdirs_sizes: Vec<u32> = subdirs
             .clone()
-            .map(|subdir| subdir.borrow().get_subdirs_sizes())
-            .flatten()
+            .flat_map(|subdir| subdir.borrow().get_subdirs_sizes())
             .collect();
 
         subdirs.for_each(|item| subdirs_sizes.push(item.borrow().get_total_size()));
@@ -89,69 +177,62 @@ impl Node {
     }
 }
 
-fn process_raw_command(raw_command: &str) -> Command {
-    let contents: Vec<&str> = raw_command.split("\n").collect();
-
-    let command = contents.first().unwrap().to_string();
-    let output = &contents[1..];
-
-    Command {
-        command: command,
-        output: output
-            .iter()
-            .map(|line| line.to_string())
-            .collect::<Vec<String>>(),
+fn add_child_to_node(name: &str, size: Option<u32>, current_node: &Rc<RefCell<Node>>) {
+    // listing the same directory twice must not count its files twice
+    if current_node.borrow().get_child_by_name(name).is_some() {
+        return;
     }
-}
 
-fn add_children_to_node(command_output: &Vec<String>, current_node: &Rc<RefCell<Node>>) {
-    for item in command_output {
-        let splitted: Vec<&str> = item.split(" ").collect();
+    let mut new_node = Node::new(name.to_string());
 
-        let new_node = Rc::new(RefCell::new(Node::new(splitted[1].to_string())));
-        let mut mut_new_node = new_node.borrow_mut();
+    new_node.size = size;
+    new_node.parent = Some(Rc::clone(current_node));
 
-        mut_new_node.parent = Some(Rc::clone(&current_node));
-
-        if DIRECTORY_CHILD.is_match(item) {
-            current_node.borrow_mut().add_child(Rc::clone(&new_node));
-        }
-        if FILE_CHILD.is_match(item) {
-            mut_new_node.size = Some(splitted[0].parse::<u32>().unwrap());
-
-            current_node.borrow_mut().add_child(Rc::clone(&new_node));
-        }
-    }
+    current_node
+        .borrow_mut()
+        .add_child(Rc::new(RefCell::new(new_node)));
 }
 
-fn build_filesystem(commands: &Vec<Command>) -> Rc<RefCell<Node>> {
+// like a shell, cd .. stays at the root and cd creates directories which were never listed
+fn build_filesystem(
+    transcript: &Vec<(usize, TranscriptLine)>,
+) -> Result<Rc<RefCell<Node>>, TranscriptError> {
     let root = Rc::new(RefCell::new(Node::new("/".to_string())));
     let mut current_node = Rc::clone(&root);
 
-    for command in commands {
-        if CD_PARENT_PATTERN.is_match(&command.command) {
-            let current_clone = Rc::clone(&current_node);
-
-            current_node = Rc::clone(current_clone.borrow().parent.as_ref().unwrap());
-        }
-
-        if CD_CHILD_PATTERN.is_match(&command.command) {
-            let splitted: Vec<&str> = command.command.split(" ").collect();
-            let child_to_access = Rc::clone(
-                current_node
-                    .borrow()
-                    .get_child_by_name(splitted[2].to_string()),
-            );
-
-            current_node = Rc::clone(&child_to_access);
-        }
-
-        if LS_PATTERN.is_match(&command.command) {
-            add_children_to_node(&command.output, &current_node)
+    for (line_number, line) in transcript {
+        match line {
+            TranscriptLine::ChangeDirectory(Destination::Root) => current_node = Rc::clone(&root),
+            TranscriptLine::ChangeDirectory(Destination::Parent) => {
+                let parent = current_node.borrow().parent.as_ref().map(Rc::clone);
+
+                if let Some(parent) = parent {
+                    current_node = parent;
+                }
+            }
+            TranscriptLine::ChangeDirectory(Destination::Child(name)) => {
+                add_child_to_node(name, None, &current_node);
+
+                let child = Rc::clone(current_node.borrow().get_child_by_name(name).unwrap());
+
+                if child.borrow().size.is_some() {
+                    return Err(TranscriptError::NotADirectory {
+                        line_number: *line_number,
+                        name: name.to_string(),
+                    });
+                }
+
+                current_node = child;
+            }
+            TranscriptLine::List => {}
+            TranscriptLine::Directory(name) => add_child_to_node(name, None, &current_node),
+            TranscriptLine::File { size, name } => {
+                add_child_to_node(name, Some(*size), &current_node)
+            }
         }
     }
 
-    root
+    return Ok(root);
 }
 
 fn get_directory_to_delete_size(filesystem: &Rc<RefCell<Node>>) -> u32 {
@@ -172,12 +253,15 @@ fn get_directory_to_delete_size(filesystem: &Rc<RefCell<Node>>) -> u32 {
 pub fn execute() {
     let content = fs::read_to_string(FILE_PATH).expect("Should have been able to read the file");
 
-    let commands = content
-        .split("\n$")
-        .map(process_raw_command)
-        .collect::<Vec<Command>>();
+    let filesystem =
+        match parse_transcript(&content).and_then(|transcript| build_filesystem(&transcript)) {
+            Ok(filesystem) => filesystem,
+            Err(error) => {
+                println!("Invalid transcript: {}", error);
 
-    let filesystem = build_filesystem(&commands);
+                return;
+            }
+        };
 
     let result_1: u32 = filesystem
         .borrow()
@@ -45,8 +45,8 @@ fn check_is_tree_visible(
 
     let is_visible_from_left = &left_view_trees.iter().all(|tree| tree < tree_to_check);
     let is_visible_from_right = &right_view_trees.iter().all(|tree| tree < tree_to_check);
-    let is_visible_from_top = &top_view_trees.iter().all(|tree| tree < &tree_to_check);
-    let is_visible_from_bottom = &bottom_view_trees.iter().all(|tree| tree < &tree_to_check);
+    let is_visible_from_top = &top_view_trees.iter().all(|tree| tree < tree_to_check);
+    let is_visible_from_bottom = &bottom_view_trees.iter().all(|tree| tree < tree_to_check);
 
     let is_visible = *is_visible_from_left
         || *is_visible_from_right
@@ -1,3 +1,7 @@
+#![allow(clippy::needless_return)]
+#![allow(clippy::ptr_arg)]
+
+mod common;
 mod day_01;
 mod day_02;
 mod day_03;
@@ -7,13 +11,59 @@ mod day_06;
 mod day_07;
 mod day_08;
 
+use std::env;
+
 fn main() {
-    // day_01::execute();
-    // day_02::execute();
-    // day_03::execute();
-    // day_04::execute();
-    // day_05::execute();
-    // day_06::execute();
-    // day_07::execute();
-    day_08::execute();
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    match args.iter().map(String::as_str).collect::<Vec<&str>>()[..] {
+        ["day02", "mapping"] => day_02::execute_mapping_analysis(),
+        ["day02", "report", part, format] => day_02::execute_tournament_report(part, format),
+        ["day02", "optimal", ref options @ ..] => day_02::execute_optimal_strategy(options),
+        ["day03", "common", compartments_count, group_size, ref options @ ..]
+            if !compartments_count.starts_with("--") =>
+        {
+            day_03::execute_common_items_with_layout(compartments_count, group_size, options)
+        }
+        ["day03", "common", ref options @ ..] => day_03::execute_common_items(options),
+        ["day03", "layout", compartments_count, group_size, ref options @ ..] => {
+            day_03::execute_with_layout(compartments_count, group_size, options)
+        }
+        ["day04", "coverage"] => day_04::execute_coverage_report(),
+        ["day04", "matrix"] => day_04::execute_overlaps_matrices(),
+        ["day04", "query", kind, target] => day_04::execute_assignments_query(kind, target),
+        ["day05", "crane", ref options @ ..] => day_05::execute_with_crane(options),
+        ["day05", "render", ref options @ ..] => day_05::execute_rendering(options),
+        ["day05", "save", step, ref options @ ..] => day_05::execute_saving(step, options),
+        ["day05", "step", step, ref options @ ..] => day_05::execute_step(step, options),
+        ["day05", "reconstruct", path, ref options @ ..] => {
+            day_05::execute_reconstruction(path, options)
+        }
+        ["day05", "generate", seed, stacks_count, max_height, instructions_count] => {
+            day_05::execute_generator(seed, stacks_count, max_height, instructions_count, None)
+        }
+        ["day05", "generate", seed, stacks_count, max_height, instructions_count, alphabet] => {
+            day_05::execute_generator(
+                seed,
+                stacks_count,
+                max_height,
+                instructions_count,
+                Some(alphabet),
+            )
+        }
+        ["day06", "all", length] => day_06::execute_all_markers(length),
+        ["day06", "segments"] => day_06::execute_segmentation(),
+        ["day06", "--follow", ref options @ ..] => day_06::execute_follow(options),
+        ["day06", "bytes", length, ref options @ ..] => day_06::execute_bytes_scan(length, options),
+        _ => {
+            // day_01::execute();
+            // day_02::execute();
+            // day_03::execute();
+            // day_04::execute();
+            // day_05::execute();
+            // day_06::execute();
+            // day_07::execute();
+            day_08::execute();
+        }
+    }
 }